@@ -0,0 +1,134 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use ga_pcb::problem::mutation::Targeting;
+use ga_pcb::problem::*;
+use rand::seq::index::sample;
+use rand::{rngs::StdRng, SeedableRng};
+
+const BOARDS: [&str; 4] = ["zad0", "zad1", "zad2", "zad3"];
+
+type Board = ((u32, u32), Vec<PinPair>);
+
+fn load_board(path: &str) -> Board {
+    let problem = load_problem(path, None);
+    (problem.dimensions(), problem.pin_locations().to_vec())
+}
+
+fn synthetic_board(dimensions: (u32, u32), nets: usize, seed: u64) -> Board {
+    let mut random = StdRng::seed_from_u64(seed);
+    let cells = sample(
+        &mut random,
+        (dimensions.0 * dimensions.1) as usize,
+        nets * 2,
+    );
+    let point = |cell: usize| (cell as u32 / dimensions.1, cell as u32 % dimensions.1);
+    let pins = cells.into_vec();
+    let pin_locations = pins
+        .chunks(2)
+        .map(|pair| (point(pair[0]), point(pair[1])))
+        .collect();
+    (dimensions, pin_locations)
+}
+
+fn boards() -> Vec<(String, Board)> {
+    let mut boards: Vec<(String, Board)> = BOARDS
+        .iter()
+        .map(|name| {
+            let path = format!("test_data/{}.txt", name);
+            (name.to_string(), load_board(&path))
+        })
+        .collect();
+    boards.push((
+        String::from("synthetic_200x200_100"),
+        synthetic_board((200, 200), 100, 0),
+    ));
+    boards
+}
+
+fn bench_generate_individual(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_individual");
+    for (name, (dimensions, pin_locations)) in boards() {
+        let mut seed = 0;
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                seed += 1;
+                generate_individual(dimensions, pin_locations.clone(), Some(seed))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_evaluate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate");
+    for (name, (dimensions, pin_locations)) in boards() {
+        let individual = generate_individual(dimensions, pin_locations, Some(0)).untracked();
+        group.bench_function(name, |b| b.iter(|| black_box(&individual).evaluate()));
+    }
+    group.finish();
+}
+
+fn bench_collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collisions");
+    for (name, (dimensions, pin_locations)) in boards() {
+        let individual = generate_individual(dimensions, pin_locations, Some(0)).untracked();
+        group.bench_function(name, |b| b.iter(|| black_box(&individual).collisions()));
+    }
+    group.finish();
+}
+
+/// A child cloned from the last individual changed on the thread, mutated and scored, the way
+/// annealing and hill climbing step from one individual to the next.
+fn bench_mutate_child(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mutate_child");
+    for (name, (dimensions, pin_locations)) in boards() {
+        let nets = pin_locations.len();
+        let mut parent = generate_individual(dimensions, pin_locations, Some(0));
+        let mut random = StdRng::seed_from_u64(0);
+        let mut index = 0;
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut child = parent.clone();
+                child.shift_segment(index, Targeting::Uniform, &mut random);
+                index = (index + 1) % nets;
+                let fitness = child.evaluate();
+                parent = child;
+                fitness
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation");
+    group.sample_size(10);
+    for (name, (dimensions, pin_locations)) in boards() {
+        let mut problem = pin_locations
+            .iter()
+            .fold(
+                ProblemBuilder::new(dimensions).seed(0),
+                |builder, &(start, end)| builder.net(start, end),
+            )
+            .build()
+            .unwrap();
+        problem.init_population(100);
+        group.bench_function(name, |b| {
+            b.iter_batched_ref(
+                || StdRng::seed_from_u64(0),
+                |random| problem.next_generation(tournament_selection, random),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_generate_individual,
+    bench_evaluate,
+    bench_collisions,
+    bench_mutate_child,
+    bench_generation
+);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! Printed circuit board routing on a grid with a genetic algorithm.
+//!
+//! A [`Problem`] holds the board dimensions, the pin pairs to connect and any obstacles. It is
+//! either read from the text format of `test_data` with [`read_problem`] or assembled with
+//! [`ProblemBuilder`]. Solutions are [`Individual`]s holding one [`Connection`] of straight
+//! [`Segment`]s per net, scored by [`Individual::fitness`].
+//!
+//! Boards can also be imported from Specctra DSN designs, and routes exported back as SES sessions,
+//! with [`problem::specctra`]; [`problem::kicad`] exports routes as KiCad track segments.
+//!
+//! Routes are searched for with
+//! - the genetic algorithm, [`Problem::genetic_search`], configured through [`GaConfig`], or its
+//!   island model [`Problem::island_search`],
+//! - [`Problem::random_search`], [`Problem::simulated_annealing`] and the multi-objective
+//!   [`Problem::nsga2_search`],
+//! - the deterministic routers in [`problem::router`].
+//!
+//! ```
+//! use ga_pcb::problem::router::NetOrder;
+//! use ga_pcb::ProblemBuilder;
+//!
+//! let problem = ProblemBuilder::new((6, 6))
+//!     .net((1, 1), (4, 4))
+//!     .net((1, 4), (4, 1))
+//!     .seed(0)
+//!     .build()
+//!     .unwrap();
+//! let routed = problem.astar_route(NetOrder::ShortestFirst);
+//! assert_eq!(routed.connection_count(), 2);
+//! println!("{}", routed.fitness());
+//! ```
+
+pub mod problem;
+
+pub use problem::{
+    generate_individual, load_problem, read_problem, save_problem, tournament_selection,
+    write_problem, BuildError, Connection, Direction, FitnessBreakdown, GaConfig, Individual,
+    PinPair, Problem, ProblemBuilder, SearchConfig, SearchResult, Segment,
+};
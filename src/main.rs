@@ -1,14 +1,360 @@
-mod problem;
-use problem::*;
+//! Command line interface: runs the searches and routers on a board file, and generates boards.
 
-fn main() {
-    let mut problem: Problem = load_problem("test_data/zad3.txt", None);
-    let result = problem.genetic_search(tournament_selection, None, None);
-    println!("{}", result.0);
-    println!("{}", result.1);
+use ga_pcb::problem::annealing::AnnealingConfig;
+use ga_pcb::problem::generator::{generate_problem, PinDistribution};
+use ga_pcb::problem::island::IslandConfig;
+use ga_pcb::problem::kicad::{copper_layer, save_kicad, KicadConfig};
+use ga_pcb::problem::local_search::hill_climb;
+use ga_pcb::problem::nsga2::export_front;
+use ga_pcb::problem::router::{NetOrder, PathFinderConfig};
+use ga_pcb::problem::specctra::{read_dsn, save_ses, DsnDesign};
+use ga_pcb::problem::*;
+use rand::{rngs::StdRng, SeedableRng};
+use std::convert::TryFrom;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "Usage: ga_pcb <command> <board> [options]
+
+Boards ending in .dsn are imported from Specctra DSN designs.
+
+Commands:
+  generate     write a random solvable board to <board>
+  ga           genetic algorithm
+  islands      island-model genetic algorithm
+  random       best of random individuals
+  anneal       simulated annealing
+  nsga2        multi-objective genetic algorithm, prints the Pareto front
+  lee          Lee maze router
+  astar        A* router
+  pathfinder   negotiated-congestion router
+  climb        hill climbing from a solution saved with --json
+
+Options:
+  --seed <n>          seed for reproducible runs
+  --iterations <n>    generations, samples, annealing steps or hill-climbing passes
+  --population <n>    GA population size
+  --threads <n>       worker threads (random, anneal) or islands (islands)
+  --json <path>       write the result as JSON (ga, islands, random, anneal)
+  --front <path>      write the Pareto front (nsga2)
+  --solution <path>   JSON result to start from (climb)
+  --kicad <path>      write the routes as a KiCad board (all but nsga2)
+  --ses <path>        write the routes as a Specctra session (DSN boards, all but nsga2)
+  --pitch <mm>        grid pitch of the KiCad board and of DSN imports
+  --width <mm>        trace width of the KiCad board and session
+  --layer <name>      copper layer of the KiCad traces (F.Cu, B.Cu, In1.Cu to In30.Cu)
+                      and of the session wires
+  --rows <n>          board rows (generate)
+  --columns <n>       board columns (generate)
+  --nets <n>          number of nets (generate)
+  --pins <kind>       pin distribution: uniform, clustered or bus (generate)";
+
+#[derive(Default)]
+struct Options {
+    seed: Option<u64>,
+    iterations: Option<u64>,
+    population: Option<usize>,
+    threads: Option<usize>,
+    json: Option<String>,
+    front: Option<String>,
+    solution: Option<String>,
+    kicad: Option<String>,
+    ses: Option<String>,
+    pitch: Option<f32>,
+    width: Option<f32>,
+    layer: Option<String>,
+    rows: Option<u32>,
+    columns: Option<u32>,
+    nets: Option<usize>,
+    pins: Option<PinDistribution>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    fn value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+        let value = value.ok_or(format!("Missing value for {}", flag))?;
+        value
+            .parse()
+            .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+    }
+
+    fn count<T>(flag: &str, count: Option<&String>) -> Result<T, String>
+    where
+        T: std::str::FromStr + Default + PartialEq,
+    {
+        let count = value(flag, count)?;
+        if count == T::default() {
+            return Err(format!("{} must be at least 1", flag));
+        }
+        Ok(count)
+    }
 
-    // let result = problem.random_search(100000, Some(4));
-    // println!("{}", result.0);
-    // println!("{}", result.1);
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--seed" => options.seed = Some(value(flag, args.next())?),
+            "--iterations" => options.iterations = Some(count(flag, args.next())?),
+            "--population" => options.population = Some(count(flag, args.next())?),
+            "--threads" => options.threads = Some(value(flag, args.next())?),
+            "--json" => options.json = Some(value(flag, args.next())?),
+            "--front" => options.front = Some(value(flag, args.next())?),
+            "--solution" => options.solution = Some(value(flag, args.next())?),
+            "--kicad" => options.kicad = Some(value(flag, args.next())?),
+            "--ses" => options.ses = Some(value(flag, args.next())?),
+            "--pitch" => options.pitch = Some(value(flag, args.next())?),
+            "--width" => options.width = Some(value(flag, args.next())?),
+            "--layer" => options.layer = Some(value(flag, args.next())?),
+            "--rows" => options.rows = Some(count(flag, args.next())?),
+            "--columns" => options.columns = Some(count(flag, args.next())?),
+            "--nets" => options.nets = Some(count(flag, args.next())?),
+            "--pins" => options.pins = Some(value(flag, args.next())?),
+            _ => return Err(format!("Unknown option {}", flag)),
+        }
+    }
+    Ok(options)
 }
 
+fn print_result(result: &SearchResult, options: &Options) -> Result<(), String> {
+    match &result.history {
+        SearchHistory::Generations(generations) => {
+            for stats in generations {
+                println!(
+                    "Generation {}: best {:.1}, diversity {:.3}, mutation rate {:.3}, crossover rate {:.3}, operator weights {:.2?}",
+                    stats.generation,
+                    stats.best.total,
+                    stats.diversity,
+                    stats.rates.mutation,
+                    stats.rates.crossover,
+                    stats.operator_weights
+                );
+            }
+        }
+        SearchHistory::Epochs(epochs) => {
+            for stats in epochs {
+                println!("Epoch {}: island bests", stats.epoch);
+                for (island, best) in stats.island_bests.iter().enumerate() {
+                    println!("  #{}: {}", island, best);
+                }
+            }
+        }
+        _ => {}
+    }
+    println!("{}", result.individual);
+    println!(
+        "Best fitness: {} after {} generations, {} evaluations, {:.1?}",
+        result.fitness, result.generations, result.evaluations, result.elapsed
+    );
+    if let Some(path) = &options.json {
+        let json = result.to_json().map_err(|error| error.to_string())?;
+        fs::write(path, json).map_err(|error| format!("Failed to write {}: {}", path, error))?;
+    }
+    Ok(())
+}
+
+fn export_kicad(
+    problem: &Problem,
+    individual: &Individual,
+    options: &Options,
+) -> Result<(), String> {
+    let path = match &options.kicad {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let mut config = KicadConfig::default();
+    if let Some(pitch) = options.pitch {
+        config.pitch = pitch;
+    }
+    if let Some(width) = options.width {
+        config.width = width;
+    }
+    if let Some(layer) = &options.layer {
+        config.layer = layer.clone();
+    }
+    save_kicad(problem, individual, &config, path)
+        .map_err(|error| format!("Failed to write {}: {}", path, error))
+}
+
+fn export_ses(
+    design: &Option<DsnDesign>,
+    individual: &Individual,
+    options: &Options,
+) -> Result<(), String> {
+    let path = match &options.ses {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let mut design = design
+        .clone()
+        .expect("run rejects --ses for boards other than DSN");
+    if let Some(width) = options.width {
+        design.width = width as f64;
+    }
+    if let Some(layer) = &options.layer {
+        design.layer = layer.clone();
+    }
+    save_ses(&design, individual, path)
+        .map_err(|error| format!("Failed to write {}: {}", path, error))
+}
+
+fn generate(path: &str, options: &Options) -> Result<(), String> {
+    let dimensions = (options.rows.unwrap_or(16), options.columns.unwrap_or(16));
+    let problem = generate_problem(
+        dimensions,
+        options.nets.unwrap_or(8),
+        options.pins.unwrap_or(PinDistribution::Uniform),
+        options.seed.unwrap_or(0),
+    )
+    .ok_or("No solvable board found, try fewer nets or a larger board")?;
+    save_problem(&problem, path).map_err(|error| format!("Failed to write {}: {}", path, error))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, board) = match args {
+        [command, board, ..] => (command.as_str(), board.as_str()),
+        _ => return Err(USAGE.to_string()),
+    };
+    let options = parse_options(&args[2..])?;
+    if command == "generate" {
+        return generate(board, &options);
+    }
+    if let (Some(_), Some(layer)) = (&options.kicad, &options.layer) {
+        if copper_layer(layer).is_none() {
+            return Err(format!("{} is not a KiCad copper layer", layer));
+        }
+    }
+    if options.ses.is_some() && !board.ends_with(".dsn") {
+        return Err("Sessions can only be written for DSN boards".to_string());
+    }
+    let (mut problem, design) = if board.ends_with(".dsn") {
+        let pitch = options.pitch.unwrap_or(KicadConfig::default().pitch);
+        let (problem, design) = read_dsn(board, pitch as f64, options.seed)
+            .map_err(|error| format!("Failed to load {}: {}", board, error))?;
+        if !design.skipped.is_empty() {
+            eprintln!(
+                "Skipped nets without exactly two pins or with pads on a shared cell: {}",
+                design.skipped.join(", ")
+            );
+        }
+        (problem, Some(design))
+    } else {
+        let problem = read_problem(board, options.seed)
+            .map_err(|error| format!("Failed to load {}: {}", board, error))?;
+        (problem, None)
+    };
+
+    // Random search counts samples in 64 bits, everything else counts in 32.
+    let iterations = match options.iterations {
+        Some(iterations) if command != "random" => {
+            Some(u32::try_from(iterations).map_err(|_| {
+                format!("--iterations must be at most {} for {}", u32::MAX, command)
+            })?)
+        }
+        _ => None,
+    };
+    let mut config = problem.config().clone();
+    if let Some(iterations) = iterations {
+        config.iterations = iterations;
+    }
+    if let Some(population) = options.population {
+        config.population = population;
+    }
+    problem.set_config(config);
+
+    let individual = match command {
+        "ga" => {
+            let result = problem.genetic_search(tournament_selection, options.seed);
+            print_result(&result, &options)?;
+            result.individual
+        }
+        "islands" => {
+            let mut islands = IslandConfig::default();
+            if let Some(threads) = options.threads {
+                islands.islands = threads;
+            }
+            let result = problem.island_search(tournament_selection, &islands, options.seed);
+            print_result(&result, &options)?;
+            result.individual
+        }
+        "random" => {
+            let result =
+                problem.random_search(options.iterations.unwrap_or(100000), options.threads);
+            print_result(&result, &options)?;
+            result.individual
+        }
+        "anneal" => {
+            let mut annealing = AnnealingConfig::default();
+            if let Some(iterations) = iterations {
+                annealing.steps = iterations;
+            }
+            if let Some(threads) = options.threads {
+                annealing.chains = threads;
+            }
+            let result = problem.simulated_annealing(&annealing, options.seed);
+            print_result(&result, &options)?;
+            result.individual
+        }
+        "nsga2" => {
+            let front = problem.nsga2_search(options.seed);
+            for (_, objectives) in &front {
+                println!(
+                    "collisions {}, length {}, segments {}",
+                    objectives[0], objectives[1], objectives[2]
+                );
+            }
+            if let Some(path) = &options.front {
+                export_front(&front, path)
+                    .map_err(|error| format!("Failed to write {}: {}", path, error))?;
+            }
+            return Ok(());
+        }
+        "lee" => {
+            let individual = problem.lee_route();
+            println!("{}", individual);
+            individual
+        }
+        "astar" => {
+            let individual = problem.astar_route(NetOrder::ShortestFirst);
+            println!("{}", individual);
+            individual
+        }
+        "pathfinder" => {
+            let result = problem.pathfinder_route(&PathFinderConfig::default());
+            println!("{}", result.individual);
+            result.individual
+        }
+        "climb" => {
+            let path = options
+                .solution
+                .as_ref()
+                .ok_or("climb needs a --solution to start from")?;
+            let mut individual = read_solution(path, &problem)
+                .map_err(|error| format!("Failed to load {}: {}", path, error))?;
+            let mut local_search = problem.config().local_search.clone();
+            if let Some(iterations) = iterations {
+                local_search.max_passes = iterations;
+            }
+            let mut random = match options.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            let stats = hill_climb(&mut individual, &local_search, &mut random);
+            println!("{}", individual);
+            println!(
+                "Improved {} -> {} with {} changes in {} passes",
+                stats.start_fitness, stats.fitness, stats.improvements, stats.passes
+            );
+            individual
+        }
+        _ => return Err(format!("Unknown command {}\n\n{}", command, USAGE)),
+    };
+    export_kicad(&problem, &individual, &options)?;
+    export_ses(&design, &individual, &options)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(message) = run(&args) {
+        eprintln!("{}", message);
+        process::exit(2);
+    }
+}
@@ -0,0 +1,139 @@
+//! Parameter control for `Problem::genetic_search`: schedules that change the mutation and
+//! crossover rates over a run, and the bookkeeping that re-weights the mutation operators by how
+//! often they improve an individual.
+
+use super::config::GaConfig;
+use serde::Serialize;
+
+const LEARNING_RATE: f32 = 0.3;
+const MIN_WEIGHT: f32 = 0.05;
+
+/// How mutation and crossover rates change over a run of `genetic_search`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum RateSchedule {
+    /// Keeps the rates from `GaConfig` for the whole run.
+    Constant,
+    /// Moves linearly from the configured rates to these ones by the last generation.
+    LinearDecay {
+        /// Mutation rate of the last generation.
+        mutation: f32,
+        /// Crossover rate of the last generation.
+        crossover: f32,
+    },
+    /// Multiplies the mutation rate by `boost` while `Problem::diversity` is below `threshold`.
+    DiversityBoost {
+        /// Diversity below which the mutation rate is boosted.
+        threshold: f32,
+        /// Factor applied to the mutation rate, which is capped at 1.
+        boost: f32,
+    },
+}
+
+/// Rates used to breed one generation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Rates {
+    /// Chance of every connection of a child to be mutated.
+    pub mutation: f32,
+    /// Chance of a child to be bred by crossover rather than copied from one parent.
+    pub crossover: f32,
+}
+
+impl RateSchedule {
+    /// Rates to breed `generation` with, given the diversity of the population it is bred from.
+    pub fn rates(&self, config: &GaConfig, generation: u32, diversity: f32) -> Rates {
+        let base = Rates {
+            mutation: config.mutation_rate,
+            crossover: config.crossover_rate,
+        };
+
+        match *self {
+            RateSchedule::Constant => base,
+            RateSchedule::LinearDecay {
+                mutation,
+                crossover,
+            } => {
+                let progress =
+                    generation as f32 / config.iterations.saturating_sub(1).max(1) as f32;
+                let progress = progress.min(1.);
+                Rates {
+                    mutation: base.mutation + (mutation - base.mutation) * progress,
+                    crossover: base.crossover + (crossover - base.crossover) * progress,
+                }
+            }
+            RateSchedule::DiversityBoost { threshold, boost } => {
+                if diversity < threshold {
+                    Rates {
+                        mutation: (base.mutation * boost).min(1.),
+                        ..base
+                    }
+                } else {
+                    base
+                }
+            }
+        }
+    }
+}
+
+/// How often each mutation operator was applied during a generation and how often that improved
+/// the fitness of the individual.
+#[derive(Clone, Debug, Default)]
+pub struct OperatorStats {
+    trials: Vec<u32>,
+    successes: Vec<u32>,
+}
+
+impl OperatorStats {
+    /// Empty stats of `operators` operators.
+    pub fn new(operators: usize) -> OperatorStats {
+        OperatorStats {
+            trials: vec![0; operators],
+            successes: vec![0; operators],
+        }
+    }
+
+    /// Counts one application of `operator`.
+    pub fn record(&mut self, operator: usize, improved: bool) {
+        self.trials[operator] += 1;
+        if improved {
+            self.successes[operator] += 1;
+        }
+    }
+
+    /// Moves `weights` towards the operators' relative success rates. Operators that were not
+    /// tried keep their weight and no weight drops below `MIN_WEIGHT`.
+    pub fn adapt(&self, weights: &mut [f32]) {
+        let success_rates: Vec<Option<f32>> = self
+            .trials
+            .iter()
+            .zip(&self.successes)
+            .map(|(&trials, &successes)| {
+                if trials > 0 {
+                    Some(successes as f32 / trials as f32)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let total_rate: f32 = success_rates.iter().flatten().sum();
+        if total_rate == 0. {
+            return;
+        }
+        let total_weight: f32 = weights
+            .iter()
+            .zip(&success_rates)
+            .filter(|(_, rate)| rate.is_some())
+            .map(|(weight, _)| weight)
+            .sum();
+
+        for (weight, rate) in weights.iter_mut().zip(success_rates) {
+            if let Some(rate) = rate {
+                let target = rate / total_rate * total_weight;
+                *weight = ((1. - LEARNING_RATE) * *weight + LEARNING_RATE * target).max(MIN_WEIGHT);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
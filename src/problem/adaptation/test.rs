@@ -0,0 +1,89 @@
+use super::*;
+
+#[test]
+fn test_linear_decay_reaches_end_rates() {
+    let config = GaConfig {
+        mutation_rate: 0.4,
+        crossover_rate: 0.8,
+        iterations: 11,
+        ..Default::default()
+    };
+    let schedule = RateSchedule::LinearDecay {
+        mutation: 0.1,
+        crossover: 0.5,
+    };
+
+    let first = schedule.rates(&config, 0, 1.);
+    assert_eq!(first.mutation, 0.4);
+    assert_eq!(first.crossover, 0.8);
+
+    let middle = schedule.rates(&config, 5, 1.);
+    assert!((middle.mutation - 0.25).abs() < 1e-6);
+    assert!((middle.crossover - 0.65).abs() < 1e-6);
+
+    for generation in IntoIterator::into_iter([10, 20]) {
+        let last = schedule.rates(&config, generation, 1.);
+        assert!((last.mutation - 0.1).abs() < 1e-6);
+        assert!((last.crossover - 0.5).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_diversity_boost_raises_mutation_at_low_diversity() {
+    let config = GaConfig {
+        mutation_rate: 0.2,
+        crossover_rate: 0.8,
+        ..Default::default()
+    };
+    let schedule = RateSchedule::DiversityBoost {
+        threshold: 0.3,
+        boost: 3.,
+    };
+
+    let diverse = schedule.rates(&config, 0, 0.5);
+    assert_eq!(diverse.mutation, 0.2);
+    let converged = schedule.rates(&config, 0, 0.1);
+    assert!((converged.mutation - 0.6).abs() < 1e-6);
+    assert_eq!(converged.crossover, 0.8);
+
+    let strong = RateSchedule::DiversityBoost {
+        threshold: 0.3,
+        boost: 10.,
+    };
+    assert_eq!(strong.rates(&config, 0, 0.1).mutation, 1.);
+}
+
+#[test]
+fn test_adapt_moves_weight_to_successful_operators() {
+    let mut stats = OperatorStats::new(3);
+    for _ in 0..10 {
+        stats.record(0, true);
+        stats.record(1, false);
+    }
+    let mut weights = [1., 1., 1.];
+    stats.adapt(&mut weights);
+
+    assert!(weights[0] > 1.);
+    assert!(weights[1] < 1.);
+    assert_eq!(
+        weights[2], 1.,
+        "operators that were not tried keep their weight"
+    );
+    assert!((weights[0] + weights[1] - 2.).abs() < 1e-6);
+
+    for _ in 0..50 {
+        stats.adapt(&mut weights);
+    }
+    assert_eq!(weights[1], MIN_WEIGHT);
+    assert!(weights.iter().all(|&weight| weight >= MIN_WEIGHT));
+}
+
+#[test]
+fn test_adapt_keeps_weights_without_successes() {
+    let mut stats = OperatorStats::new(2);
+    stats.record(0, false);
+    stats.record(1, false);
+    let mut weights = [0.7, 0.3];
+    stats.adapt(&mut weights);
+    assert_eq!(weights, [0.7, 0.3]);
+}
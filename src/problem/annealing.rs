@@ -0,0 +1,236 @@
+//! Simulated annealing over single individuals, with the mutation operators as neighbourhood,
+//! several independent chains and restarts from the best state found so far.
+
+use super::individual::{generate_individual_with_obstacles, Individual, PinPair};
+use super::mutation::{Mutation, MutationKind, Targeting};
+use super::result::{SearchConfig, SearchHistory, SearchResult};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rand::distributions::WeightedIndex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// How the temperature falls from `initial_temperature` to `final_temperature` over the steps of
+/// one restart.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum CoolingSchedule {
+    /// Geometric cooling, the same factor every step.
+    Exponential,
+    /// The same decrement every step.
+    Linear,
+}
+
+/// Parameters of `Problem::simulated_annealing`.
+#[derive(Clone, Debug, Serialize)]
+pub struct AnnealingConfig {
+    /// Temperature at the first step of every restart.
+    pub initial_temperature: f32,
+    /// Temperature reached at the last step of every restart.
+    pub final_temperature: f32,
+    /// How the temperature falls in between.
+    pub cooling: CoolingSchedule,
+    /// Steps of a single cooling run.
+    pub steps: u32,
+    /// Number of times a chain is reheated after cooling down, continuing from its best state.
+    pub restarts: u32,
+    /// Independent chains, each run on its own thread.
+    pub chains: usize,
+    /// Mutation operators making up the neighbourhood, with their relative probabilities.
+    pub mutations: Vec<(MutationKind, f32)>,
+    /// Which segments the mutation operators pick.
+    pub targeting: Targeting,
+    /// Every how many steps the temperature and fitness are recorded.
+    pub trace_every: u32,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        AnnealingConfig {
+            initial_temperature: 50.,
+            final_temperature: 0.05,
+            cooling: CoolingSchedule::Exponential,
+            steps: 20000,
+            restarts: 2,
+            chains: num_cpus::get() / 2,
+            mutations: vec![
+                (MutationKind::ShiftSegment, 1.),
+                (MutationKind::SplitSegment, 1.),
+                (MutationKind::Straighten, 1.),
+                (MutationKind::Reroute, 1.),
+                (MutationKind::Regenerate, 0.2),
+            ],
+            targeting: Targeting::Collisions,
+            trace_every: 100,
+        }
+    }
+}
+
+/// State of one chain at a recorded step.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct AnnealingStep {
+    /// Number of the chain, from 0.
+    pub chain: usize,
+    /// Restarts the chain has made before this step.
+    pub restart: u32,
+    /// Step within the restart.
+    pub step: u32,
+    /// Temperature the step was taken at.
+    pub temperature: f32,
+    /// Fitness of the chain's current individual.
+    pub fitness: f32,
+    /// Best fitness the chain has reached so far.
+    pub best_fitness: f32,
+}
+
+impl CoolingSchedule {
+    fn temperature(&self, config: &AnnealingConfig, step: u32) -> f32 {
+        let progress = step as f32 / config.steps.max(1) as f32;
+        match self {
+            CoolingSchedule::Exponential => {
+                config.initial_temperature
+                    * (config.final_temperature / config.initial_temperature).powf(progress)
+            }
+            CoolingSchedule::Linear => {
+                config.initial_temperature
+                    + (config.final_temperature - config.initial_temperature) * progress
+            }
+        }
+    }
+}
+
+/// Runs `config.chains` independent annealing chains, each on its own thread and starting from
+/// its own random individual. Chain `i` is seeded with `seed + i` when a seed is given. Returns
+/// the best individual over all chains, with the recorded steps of every chain as its history.
+pub fn simulated_annealing(
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+    obstacles: &[(u32, u32)],
+    config: &AnnealingConfig,
+    seed: Option<u64>,
+) -> SearchResult {
+    let now = Instant::now();
+    let obstacles: Arc<[(u32, u32)]> = obstacles.into();
+    let m = MultiProgress::new();
+    let sty = ProgressStyle::default_bar()
+        .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
+        .progress_chars("=> ");
+
+    let handles = (0..config.chains.max(1))
+        .map(|chain| {
+            let pin_locations = pin_locations.to_vec();
+            let obstacles = obstacles.clone();
+            let config = config.clone();
+            let pb = m.add(ProgressBar::new(
+                config.steps as u64 * (config.restarts as u64 + 1),
+            ));
+            pb.set_prefix(&format!("Chain #{}", chain));
+            pb.set_style(sty.clone());
+            thread::spawn(move || {
+                let mut random = match seed {
+                    Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(chain as u64)),
+                    None => StdRng::from_entropy(),
+                };
+                let start = generate_individual_with_obstacles(
+                    dimensions,
+                    pin_locations,
+                    obstacles,
+                    Some(random.gen()),
+                );
+                let result = anneal(chain, start, &config, &mut random, &pb);
+                pb.finish();
+                result
+            })
+        })
+        .collect::<Vec<thread::JoinHandle<_>>>();
+
+    m.join_and_clear().unwrap();
+
+    let mut trace = vec![];
+    let mut best: Option<(Individual, f32)> = None;
+    for handle in handles {
+        let (individual, fitness, chain_trace) = handle.join().unwrap();
+        trace.extend(chain_trace);
+        if best.as_ref().is_none_or(|best| fitness < best.1) {
+            best = Some((individual, fitness));
+        }
+    }
+
+    let chains = config.chains.max(1) as u64;
+    let evaluations = chains * (1 + config.steps as u64 * (config.restarts as u64 + 1));
+    SearchResult::new(
+        best.unwrap().0,
+        now.elapsed(),
+        evaluations,
+        0,
+        seed,
+        SearchConfig::Annealing(config.clone()),
+    )
+    .with_history(SearchHistory::Annealing(trace))
+}
+
+fn anneal(
+    chain: usize,
+    start: Individual,
+    config: &AnnealingConfig,
+    random: &mut StdRng,
+    pb: &ProgressBar,
+) -> (Individual, f32, Vec<AnnealingStep>) {
+    let choice = WeightedIndex::new(config.mutations.iter().map(|operator| operator.1))
+        .expect("Annealing needs at least one mutation operator");
+    let nets = start.connection_count();
+
+    let mut current_fitness = start.evaluate();
+    let mut current = start;
+    let mut best = (current.clone(), current_fitness);
+    let mut trace = vec![];
+
+    for restart in 0..=config.restarts {
+        if restart > 0 {
+            current = best.0.clone();
+            current_fitness = best.1;
+        }
+
+        for step in 0..config.steps {
+            let temperature = config.cooling.temperature(config, step);
+
+            if nets > 0 {
+                // Moves are tried in place and undone when rejected, so the occupancy counts
+                // tracked for `current` stay valid from one step to the next.
+                let index = random.gen_range(0..nets);
+                let previous = current.connections()[index].clone();
+                let operator = config.mutations[random.sample(&choice)].0;
+                operator.mutate(&mut current, index, config.targeting, random);
+                let candidate_fitness = current.evaluate();
+
+                let delta = candidate_fitness - current_fitness;
+                if delta <= 0. || random.gen::<f32>() < (-delta / temperature).exp() {
+                    current_fitness = candidate_fitness;
+                    if current_fitness < best.1 {
+                        best = (current.clone(), current_fitness);
+                    }
+                } else {
+                    current.restore_connection(index, previous);
+                }
+            }
+
+            if step % config.trace_every.max(1) == 0 {
+                trace.push(AnnealingStep {
+                    chain,
+                    restart,
+                    step,
+                    temperature,
+                    fitness: current_fitness,
+                    best_fitness: best.1,
+                });
+            }
+            pb.inc(1);
+        }
+    }
+
+    (best.0, best.1, trace)
+}
+
+#[cfg(test)]
+mod test;
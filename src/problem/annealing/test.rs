@@ -0,0 +1,76 @@
+use super::*;
+use crate::problem::fixtures::{assert_valid_individual, crossing_problem, CROSSING_PINS};
+use crate::problem::individual::generate_individual;
+
+fn small_config() -> AnnealingConfig {
+    AnnealingConfig {
+        steps: 300,
+        restarts: 1,
+        chains: 2,
+        trace_every: 10,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_cooling_schedules() {
+    let config = AnnealingConfig {
+        initial_temperature: 100.,
+        final_temperature: 1.,
+        steps: 10,
+        ..Default::default()
+    };
+
+    let exponential = CoolingSchedule::Exponential;
+    assert_eq!(exponential.temperature(&config, 0), 100.);
+    assert!((exponential.temperature(&config, 5) - 10.).abs() < 1e-3);
+    assert!((exponential.temperature(&config, 10) - 1.).abs() < 1e-4);
+
+    let linear = CoolingSchedule::Linear;
+    assert_eq!(linear.temperature(&config, 0), 100.);
+    assert!((linear.temperature(&config, 5) - 50.5).abs() < 1e-4);
+    assert!((linear.temperature(&config, 10) - 1.).abs() < 1e-4);
+
+    for schedule in IntoIterator::into_iter([exponential, linear]) {
+        let temperatures: Vec<f32> = (0..=10)
+            .map(|step| schedule.temperature(&config, step))
+            .collect();
+        assert!(temperatures.windows(2).all(|pair| pair[1] < pair[0]));
+    }
+}
+
+#[test]
+fn test_annealing_seeded() {
+    let config = small_config();
+    let first = simulated_annealing((6, 6), &CROSSING_PINS, &[], &config, Some(u64::MAX));
+    let second =
+        crossing_problem(0, Default::default()).simulated_annealing(&config, Some(u64::MAX));
+
+    assert_eq!(first.fitness, second.fitness);
+    assert_eq!(first.individual.distance(&second.individual), 0.);
+    assert_eq!(first.history, second.history);
+    match &first.history {
+        SearchHistory::Annealing(trace) => assert_eq!(trace.len(), 2 * 2 * 300 / 10),
+        history => panic!("Expected annealing steps, got {:?}", history),
+    }
+    assert_eq!(first.evaluations, 2 * (1 + 300 * 2));
+    assert_valid_individual(&first.individual, (6, 6), &CROSSING_PINS);
+}
+
+#[test]
+fn test_annealing_never_worse_than_start() {
+    let config = small_config();
+    for seed in 0..5 {
+        let start = generate_individual((6, 6), CROSSING_PINS.to_vec(), Some(seed));
+        let start_fitness = start.evaluate();
+        let mut random = StdRng::seed_from_u64(seed);
+        let (best, fitness, trace) = anneal(0, start, &config, &mut random, &ProgressBar::hidden());
+
+        assert_eq!(best.evaluate(), fitness);
+        assert!(fitness <= start_fitness);
+        assert!(trace
+            .windows(2)
+            .all(|pair| pair[1].best_fitness <= pair[0].best_fitness));
+        assert_valid_individual(&best, (6, 6), &CROSSING_PINS);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{GaConfig, PinPair, Problem};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+/// Why `ProblemBuilder::build` rejected a board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildError {
+    /// The board has no rows or no columns.
+    EmptyBoard,
+    /// No net was added.
+    NoNets,
+    /// A pin or obstacle lies outside the board.
+    OutOfBounds((u32, u32)),
+    /// Both pins of the net with this index are on the same cell.
+    SinglePinNet(usize),
+    /// A cell is used by more than one pin or obstacle.
+    SharedCell((u32, u32)),
+    /// A net name is used twice, or contains a `;` or a line break.
+    InvalidName(String),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::EmptyBoard => write!(f, "Board has no cells"),
+            BuildError::NoNets => write!(f, "Board has no nets"),
+            BuildError::OutOfBounds(point) => write!(f, "Cell {:?} is outside the board", point),
+            BuildError::SinglePinNet(net) => write!(f, "Both pins of net {} are the same", net),
+            BuildError::SharedCell(point) => write!(f, "Cell {:?} is used twice", point),
+            BuildError::InvalidName(name) => write!(f, "Invalid net name {:?}", name),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// Assembles a `Problem` in code, the counterpart of reading a board file with `io::read_problem`.
+#[derive(Clone, Debug, Default)]
+pub struct ProblemBuilder {
+    dimensions: (u32, u32),
+    nets: Vec<(PinPair, Option<String>)>,
+    obstacles: Vec<(u32, u32)>,
+    seed: Option<u64>,
+    config: GaConfig,
+}
+
+impl ProblemBuilder {
+    /// Empty board of `dimensions` rows and columns, with the default `GaConfig`.
+    pub fn new(dimensions: (u32, u32)) -> ProblemBuilder {
+        ProblemBuilder {
+            dimensions,
+            ..Default::default()
+        }
+    }
+
+    /// Adds a net connecting `start` and `end`.
+    pub fn net(mut self, start: (u32, u32), end: (u32, u32)) -> ProblemBuilder {
+        self.nets.push(((start, end), None));
+        self
+    }
+
+    /// Adds a net with a name, which the SES and KiCad exports use.
+    pub fn named_net(mut self, name: &str, start: (u32, u32), end: (u32, u32)) -> ProblemBuilder {
+        self.nets.push(((start, end), Some(name.to_string())));
+        self
+    }
+
+    /// Marks a cell no trace may use.
+    pub fn obstacle(mut self, point: (u32, u32)) -> ProblemBuilder {
+        self.obstacles.push(point);
+        self
+    }
+
+    /// Marks every cell of `points` as an obstacle.
+    pub fn obstacles(mut self, points: impl IntoIterator<Item = (u32, u32)>) -> ProblemBuilder {
+        self.obstacles.extend(points);
+        self
+    }
+
+    /// Seed of the random individuals `Problem::init_population` generates.
+    pub fn seed(mut self, seed: u64) -> ProblemBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Parameters of the genetic searches run on the problem.
+    pub fn config(mut self, config: GaConfig) -> ProblemBuilder {
+        self.config = config;
+        self
+    }
+
+    /// Checks that the board has cells and nets, that every pin and obstacle is on the board and
+    /// on a cell of its own, and that net names are unique.
+    pub fn build(self) -> Result<Problem, BuildError> {
+        let (rows, columns) = self.dimensions;
+        if rows == 0 || columns == 0 {
+            return Err(BuildError::EmptyBoard);
+        }
+        if self.nets.is_empty() {
+            return Err(BuildError::NoNets);
+        }
+
+        let mut names = HashSet::new();
+        for (index, ((start, end), name)) in self.nets.iter().enumerate() {
+            if start == end {
+                return Err(BuildError::SinglePinNet(index));
+            }
+            if let Some(name) = name {
+                if name.contains(&[';', '\n', '\r'][..]) || !names.insert(name) {
+                    return Err(BuildError::InvalidName(name.clone()));
+                }
+            }
+        }
+
+        let mut used = HashSet::new();
+        let pins = self
+            .nets
+            .iter()
+            .flat_map(|((start, end), _)| vec![*start, *end]);
+        for point in pins.chain(self.obstacles.iter().copied()) {
+            if point.0 >= rows || point.1 >= columns {
+                return Err(BuildError::OutOfBounds(point));
+            }
+            if !used.insert(point) {
+                return Err(BuildError::SharedCell(point));
+            }
+        }
+
+        let (pin_locations, net_names) = self.nets.into_iter().unzip();
+        let mut problem = Problem::new(self.dimensions, pin_locations, self.seed);
+        problem.net_names = net_names;
+        problem.obstacles = self.obstacles.into();
+        problem.set_config(self.config);
+        Ok(problem)
+    }
+}
+
+#[cfg(test)]
+mod test;
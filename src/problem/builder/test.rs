@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn test_problem_builder_validation() {
+    let builder = ProblemBuilder::new((6, 6)).named_net("a", (1, 1), (4, 4));
+    assert!(builder.clone().build().is_ok());
+    assert_eq!(
+        ProblemBuilder::new((0, 6))
+            .net((0, 0), (0, 1))
+            .build()
+            .err(),
+        Some(BuildError::EmptyBoard)
+    );
+    assert_eq!(
+        ProblemBuilder::new((6, 6)).build().err(),
+        Some(BuildError::NoNets)
+    );
+    assert_eq!(
+        builder.clone().net((1, 2), (6, 2)).build().err(),
+        Some(BuildError::OutOfBounds((6, 2)))
+    );
+    assert_eq!(
+        builder.clone().obstacle((4, 4)).build().err(),
+        Some(BuildError::SharedCell((4, 4)))
+    );
+    assert_eq!(
+        builder.clone().net((2, 2), (2, 2)).build().err(),
+        Some(BuildError::SinglePinNet(1))
+    );
+    assert_eq!(
+        builder.named_net("a", (2, 2), (3, 3)).build().err(),
+        Some(BuildError::InvalidName("a".to_string()))
+    );
+}
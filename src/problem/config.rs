@@ -0,0 +1,72 @@
+//! Parameters of the genetic algorithm.
+
+use super::adaptation::RateSchedule;
+use super::crossover::CrossoverKind;
+use super::diversity::Niching;
+use super::local_search::LocalSearchConfig;
+use super::mutation::{MutationKind, Targeting};
+use super::steady_state::Replacement;
+use serde::Serialize;
+
+const CROSSOVER: f32 = 0.8;
+const MUTATION: f32 = 0.10;
+const ITERATIONS: u32 = 1000;
+const POPULATION: usize = 10000;
+const BATCH_SIZE: usize = 3;
+
+/// Parameters of `Problem::genetic_search`.
+#[derive(Clone, Debug, Serialize)]
+pub struct GaConfig {
+    /// Chance of a child to be bred by crossover rather than copied from one parent.
+    pub crossover_rate: f32,
+    /// Chance of every connection of a child to be mutated.
+    pub mutation_rate: f32,
+    /// Generations to breed.
+    pub iterations: u32,
+    /// Individuals in every generation.
+    pub population: usize,
+    /// Individuals drawn for every tournament of `tournament_selection`.
+    pub batch_size: usize,
+    /// Operator combining the two parents of a child.
+    pub crossover: CrossoverKind,
+    /// Mutation operators with their relative probabilities.
+    pub mutations: Vec<(MutationKind, f32)>,
+    /// Which connections and segments the mutation operators pick.
+    pub targeting: Targeting,
+    /// How `crossover_rate` and `mutation_rate` change over a run.
+    pub rate_schedule: RateSchedule,
+    /// Re-weights the mutation operators every generation by how often they improved fitness.
+    pub adapt_operators: bool,
+    /// Number of best individuals improved with `local_search::hill_climb` every generation.
+    pub memetic_top_k: usize,
+    /// Parameters of the hill climbing of `memetic_top_k`.
+    pub local_search: LocalSearchConfig,
+    /// How the population is kept from converging.
+    pub niching: Niching,
+    /// Replaces individuals routed exactly like an earlier one with new random individuals.
+    pub eliminate_duplicates: bool,
+    /// How offspring enter the population.
+    pub replacement: Replacement,
+}
+
+impl Default for GaConfig {
+    fn default() -> Self {
+        GaConfig {
+            crossover_rate: CROSSOVER,
+            mutation_rate: MUTATION,
+            iterations: ITERATIONS,
+            population: POPULATION,
+            batch_size: BATCH_SIZE,
+            crossover: CrossoverKind::SingleNet,
+            mutations: vec![(MutationKind::ShiftSegment, 1.)],
+            targeting: Targeting::Uniform,
+            rate_schedule: RateSchedule::Constant,
+            adapt_operators: false,
+            memetic_top_k: 0,
+            local_search: LocalSearchConfig::default(),
+            niching: Niching::None,
+            eliminate_duplicates: false,
+            replacement: Replacement::Generational,
+        }
+    }
+}
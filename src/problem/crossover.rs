@@ -0,0 +1,101 @@
+//! Crossover operators exchanging whole nets between two parents.
+
+use super::individual::Individual;
+use rand::seq::index::sample;
+use rand::{rngs::StdRng, Rng};
+use serde::Serialize;
+
+/// Combines two parents into a child. The child starts as a copy of the first parent and takes
+/// some of its nets from `other`.
+pub trait Crossover {
+    /// Moves nets of `other` into `child`.
+    fn crossover(&self, child: &mut Individual, other: &Individual, random: &mut StdRng);
+}
+
+/// Replaces a single, randomly chosen net with the one from the other parent.
+pub struct SingleNet;
+
+/// Takes every net from either parent with equal probability.
+pub struct Uniform;
+
+/// Cuts the net list at `k` random points and alternates parents between the cuts.
+pub struct KPoint(pub usize);
+
+/// Takes every net from the parent in which that net has fewer collisions.
+pub struct FitnessAware;
+
+impl Crossover for SingleNet {
+    fn crossover(&self, child: &mut Individual, other: &Individual, random: &mut StdRng) {
+        child.crossover(other, random.gen());
+    }
+}
+
+impl Crossover for Uniform {
+    fn crossover(&self, child: &mut Individual, other: &Individual, random: &mut StdRng) {
+        for index in 0..child.connection_count() {
+            if random.gen::<bool>() {
+                child.inherit(other, index);
+            }
+        }
+    }
+}
+
+impl Crossover for KPoint {
+    fn crossover(&self, child: &mut Individual, other: &Individual, random: &mut StdRng) {
+        let nets = child.connection_count();
+        if nets < 2 {
+            return;
+        }
+
+        let mut cuts = sample(random, nets - 1, self.0.clamp(1, nets - 1)).into_vec();
+        cuts.sort_unstable();
+
+        let mut from_other = false;
+        let mut cuts = cuts.into_iter().map(|cut| cut + 1).peekable();
+        for index in 0..nets {
+            if cuts.peek() == Some(&index) {
+                cuts.next();
+                from_other = !from_other;
+            }
+            if from_other {
+                child.inherit(other, index);
+            }
+        }
+    }
+}
+
+impl Crossover for FitnessAware {
+    fn crossover(&self, child: &mut Individual, other: &Individual, _random: &mut StdRng) {
+        let own = child.connection_collisions();
+        let others = other.connection_collisions();
+        for (index, (own, other_collisions)) in own.into_iter().zip(others).enumerate() {
+            if other_collisions < own {
+                child.inherit(other, index);
+            }
+        }
+    }
+}
+
+/// Crossover operator selectable in `GaConfig`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum CrossoverKind {
+    /// See `SingleNet`.
+    SingleNet,
+    /// See `Uniform`.
+    Uniform,
+    /// See `KPoint`.
+    KPoint(usize),
+    /// See `FitnessAware`.
+    FitnessAware,
+}
+
+impl Crossover for CrossoverKind {
+    fn crossover(&self, child: &mut Individual, other: &Individual, random: &mut StdRng) {
+        match *self {
+            CrossoverKind::SingleNet => SingleNet.crossover(child, other, random),
+            CrossoverKind::Uniform => Uniform.crossover(child, other, random),
+            CrossoverKind::KPoint(k) => KPoint(k).crossover(child, other, random),
+            CrossoverKind::FitnessAware => FitnessAware.crossover(child, other, random),
+        }
+    }
+}
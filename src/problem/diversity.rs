@@ -0,0 +1,154 @@
+//! Measuring how different the individuals of a population are, and the niching schemes that
+//! keep it from converging: fitness sharing, deterministic crowding and duplicate elimination.
+
+use super::individual::{generate_individual_with_obstacles, Individual, PinPair};
+use rand::{rngs::StdRng, Rng};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SHARING_SAMPLES: usize = 20;
+
+/// How `genetic_search` keeps the population from converging to clones of one individual.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Niching {
+    /// Selection and replacement only look at the fitness.
+    None,
+    /// Fitness sharing: selection sees the fitness multiplied by the niche count, the sum of
+    /// `1 - (distance / radius)^alpha` over the individuals closer than `radius`, estimated from a
+    /// sample of the population.
+    Sharing {
+        /// Distance within which two individuals share their fitness.
+        radius: f32,
+        /// Shape of the sharing function, 1 for a linear fall-off.
+        alpha: f32,
+    },
+    /// Deterministic crowding: the population is paired up at random, the two children of a pair
+    /// of parents only compete against the parent closest to them, and the better of each pair
+    /// survives.
+    Crowding,
+}
+
+/// Mean Jaccard distance between the cells of matching nets, as returned by
+/// `Individual::net_cells`: 0 for identical routes and 1 when no net shares a single cell.
+pub fn cells_distance(a: &[Vec<u32>], b: &[Vec<u32>]) -> f32 {
+    if a.is_empty() {
+        return 0.;
+    }
+    let total: f32 = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let shared = intersection(a, b);
+            let union = a.len() + b.len() - shared;
+            if union == 0 {
+                0.
+            } else {
+                1. - shared as f32 / union as f32
+            }
+        })
+        .sum();
+    total / a.len() as f32
+}
+
+fn intersection(a: &[u32], b: &[u32]) -> usize {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if a[i] > b[j] {
+            j += 1;
+        } else {
+            shared += 1;
+            i += 1;
+            j += 1;
+        }
+    }
+    shared
+}
+
+/// Mean `Individual::distance` over `pairs` random pairs of the population: 0 once it consists of
+/// clones of one individual.
+pub fn population_diversity(
+    population: &[(Individual, f32)],
+    pairs: usize,
+    random: &mut StdRng,
+) -> f32 {
+    if population.len() < 2 || pairs == 0 {
+        return 0.;
+    }
+    let mut total = 0.;
+    for _ in 0..pairs {
+        let a = random.gen_range(0..population.len());
+        let b = (a + random.gen_range(1..population.len())) % population.len();
+        total += population[a].0.distance(&population[b].0);
+    }
+    total / pairs as f32
+}
+
+/// Fitness of every individual scaled by its niche count, see `Niching::Sharing`.
+pub fn shared_fitness(
+    population: &[(Individual, f32)],
+    radius: f32,
+    alpha: f32,
+    random: &mut StdRng,
+) -> Vec<f32> {
+    if population.len() < 2 {
+        return population.iter().map(|item| item.1).collect();
+    }
+    let cells: Vec<Vec<Vec<u32>>> = population.iter().map(|item| item.0.net_cells()).collect();
+    let samples = SHARING_SAMPLES.min(population.len() - 1);
+    let scale = (population.len() - 1) as f32 / samples as f32;
+
+    (0..population.len())
+        .map(|a| {
+            let mut niche = 0.;
+            for _ in 0..samples {
+                let b = (a + random.gen_range(1..population.len())) % population.len();
+                let distance = cells_distance(&cells[a], &cells[b]);
+                if distance < radius {
+                    niche += 1. - (distance / radius).powf(alpha);
+                }
+            }
+            population[a].1 * (1. + niche * scale)
+        })
+        .collect()
+}
+
+/// Replaces every individual routed cell for cell like an earlier one with a freshly generated
+/// individual. Returns the number of replaced duplicates.
+pub fn eliminate_duplicates(
+    population: &mut [(Individual, f32)],
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+    obstacles: &[(u32, u32)],
+    random: &mut StdRng,
+) -> usize {
+    let mut seen: HashMap<u32, Vec<Vec<Vec<u32>>>> = HashMap::new();
+    let mut duplicates = vec![];
+    for (index, (individual, points)) in population.iter().enumerate() {
+        let cells = individual.net_cells();
+        let same_fitness = seen.entry(points.to_bits()).or_default();
+        if same_fitness.contains(&cells) {
+            duplicates.push(index);
+        } else {
+            same_fitness.push(cells);
+        }
+    }
+
+    let obstacles: Arc<[(u32, u32)]> = obstacles.into();
+    for &index in &duplicates {
+        let individual = generate_individual_with_obstacles(
+            dimensions,
+            pin_locations.to_vec(),
+            obstacles.clone(),
+            Some(random.gen()),
+        );
+        let points = individual.evaluate();
+        population[index] = (individual, points);
+    }
+    duplicates.len()
+}
+
+#[cfg(test)]
+mod test;
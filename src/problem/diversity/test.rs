@@ -0,0 +1,104 @@
+use super::*;
+use crate::problem::fixtures::{assert_valid_individual, crossing_problem, CROSSING_PINS};
+use crate::problem::individual::generate_individual;
+use crate::problem::{tournament_selection, GaConfig, Problem};
+use rand::SeedableRng;
+
+/// Number of differently routed individuals in the population.
+fn distinct(problem: &Problem) -> usize {
+    let mut cells: Vec<Vec<Vec<u32>>> = problem
+        .population
+        .iter()
+        .map(|(individual, _)| individual.net_cells())
+        .collect();
+    cells.sort();
+    cells.dedup();
+    cells.len()
+}
+
+fn evolve(niching: Niching, seed: u64) -> Problem {
+    let mut problem = crossing_problem(
+        seed,
+        GaConfig {
+            population: 20,
+            niching,
+            ..Default::default()
+        },
+    );
+    problem.init_population(20);
+    let mut random = StdRng::seed_from_u64(seed);
+    for _ in 0..15 {
+        problem.next_generation(tournament_selection, &mut random);
+    }
+    problem
+}
+
+#[test]
+fn test_eliminate_duplicates() {
+    let individual = generate_individual((6, 6), CROSSING_PINS.to_vec(), Some(0));
+    let points = individual.evaluate();
+    let mut population = vec![(individual.clone(), points); 4];
+    let replaced = eliminate_duplicates(
+        &mut population,
+        (6, 6),
+        &CROSSING_PINS,
+        &[],
+        &mut StdRng::seed_from_u64(0),
+    );
+    assert_eq!(replaced, 3);
+    assert_eq!(population[0].0.distance(&individual), 0.);
+    for (individual, points) in &population {
+        assert_eq!(*points, individual.evaluate());
+        assert_valid_individual(individual, (6, 6), &CROSSING_PINS);
+    }
+}
+
+#[test]
+fn test_niching_keeps_population_valid() {
+    for niching in IntoIterator::into_iter([
+        Niching::Sharing {
+            radius: 0.5,
+            alpha: 1.,
+        },
+        Niching::Crowding,
+    ]) {
+        let mut problem = crossing_problem(
+            1,
+            GaConfig {
+                population: 20,
+                niching,
+                eliminate_duplicates: true,
+                ..Default::default()
+            },
+        );
+        problem.init_population(20);
+        let mut random = StdRng::seed_from_u64(1);
+        for _ in 0..5 {
+            problem.next_generation(tournament_selection, &mut random);
+            assert_eq!(problem.population.len(), 20);
+            assert!((0. ..=1.).contains(&problem.diversity()));
+            for (individual, points) in &problem.population {
+                assert_eq!(*points, individual.evaluate());
+                assert_valid_individual(individual, (6, 6), &CROSSING_PINS);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_crowding_keeps_more_distinct_individuals() {
+    for seed in 0..5 {
+        let baseline = evolve(Niching::None, seed);
+        let crowding = evolve(Niching::Crowding, seed);
+        assert!(
+            distinct(&crowding) > distinct(&baseline),
+            "seed {}: crowding kept {} distinct individuals, the baseline {}",
+            seed,
+            distinct(&crowding),
+            distinct(&baseline)
+        );
+        for (individual, _) in &crowding.population {
+            assert_valid_individual(individual, (6, 6), &CROSSING_PINS);
+        }
+    }
+}
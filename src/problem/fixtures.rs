@@ -0,0 +1,72 @@
+//! Boards and checks shared by the unit tests of the problem modules.
+
+use super::{Connection, Direction, GaConfig, Individual, PinPair, Problem, ProblemBuilder};
+
+/// Two nets crossing on a 6x6 board, small enough for whole searches to run in a test.
+pub(crate) const CROSSING_PINS: [PinPair; 2] = [((1, 1), (4, 4)), ((1, 4), (4, 1))];
+
+/// The board of `CROSSING_PINS` seeded with `seed` and searched with `config`.
+pub(crate) fn crossing_problem(seed: u64, config: GaConfig) -> Problem {
+    CROSSING_PINS
+        .iter()
+        .fold(ProblemBuilder::new((6, 6)), |builder, &(start, end)| {
+            builder.net(start, end)
+        })
+        .seed(seed)
+        .config(config)
+        .build()
+        .unwrap()
+}
+
+/// Asserts that `connection` stays on the board and leads from its start to its end pin.
+fn assert_valid_connection(connection: &Connection, dimensions: (u32, u32)) {
+    let mut point = (connection.start().0 as i64, connection.start().1 as i64);
+    let mut total_length = 0;
+
+    for segment in connection.segments() {
+        assert!(segment.length > 0, "{:?} has an empty segment", connection);
+        for _ in 0..segment.length {
+            point = match segment.direction {
+                Direction::North => (point.0 - 1, point.1),
+                Direction::South => (point.0 + 1, point.1),
+                Direction::East => (point.0, point.1 + 1),
+                Direction::West => (point.0, point.1 - 1),
+            };
+            assert!(
+                point.0 >= 0
+                    && point.1 >= 0
+                    && point.0 < dimensions.0 as i64
+                    && point.1 < dimensions.1 as i64,
+                "{:?} leaves the board at {:?}",
+                connection,
+                point
+            );
+        }
+        total_length += segment.length as usize;
+    }
+
+    assert_eq!(
+        point,
+        (connection.end().0 as i64, connection.end().1 as i64),
+        "{:?} does not reach its end pin",
+        connection
+    );
+
+    let points = connection.following_points();
+    assert_eq!(points.len(), total_length + 1);
+    assert_eq!(points.first(), Some(&connection.start()));
+    assert_eq!(points.last(), Some(&connection.end()));
+}
+
+/// Asserts that `individual` connects exactly `pin_locations` with valid connections.
+pub(crate) fn assert_valid_individual(
+    individual: &Individual,
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+) {
+    assert_eq!(individual.connections().len(), pin_locations.len());
+    for (connection, pins) in individual.connections().iter().zip(pin_locations) {
+        assert_eq!((connection.start(), connection.end()), *pins);
+        assert_valid_connection(connection, dimensions);
+    }
+}
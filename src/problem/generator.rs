@@ -0,0 +1,165 @@
+//! Random boards for benchmarking, with uniformly spread, clustered or bus-like pins.
+
+use super::builder::ProblemBuilder;
+use super::individual::PinPair;
+use super::router::{cell, lee::wavefront};
+use super::Problem;
+use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::str::FromStr;
+
+const MAX_ATTEMPTS: usize = 100;
+const MAX_DRAWS: usize = 1000;
+const CLUSTERS: usize = 4;
+const SPREAD: u32 = 3;
+
+/// Where `generate_problem` puts the pins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PinDistribution {
+    /// Anywhere on the board.
+    Uniform,
+    /// Within `spread` cells of one of `clusters` random centres.
+    Clustered {
+        /// Number of centres.
+        clusters: usize,
+        /// Largest distance of a pin from its centre along either axis.
+        spread: u32,
+    },
+    /// Both pins of every net on one of the parallel lanes two cells apart, all running along the
+    /// same axis, like `test_data/zad3.txt`.
+    Bus,
+}
+
+impl FromStr for PinDistribution {
+    type Err = String;
+
+    /// Parses `uniform`, `clustered` or `bus`, clustering with default parameters.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "uniform" => Ok(PinDistribution::Uniform),
+            "clustered" => Ok(PinDistribution::Clustered {
+                clusters: CLUSTERS,
+                spread: SPREAD,
+            }),
+            "bus" => Ok(PinDistribution::Bus),
+            _ => Err(format!("Unknown pin distribution {}", name)),
+        }
+    }
+}
+
+/// Random board with `nets` nets placed according to `distribution`. Boards are solvable by
+/// construction: every net is routed around the traces of the earlier ones before the next is
+/// placed, and its pins are drawn again when that fails. Returns `None` when no such board turns
+/// up within `MAX_ATTEMPTS` tries, e.g. because the board is too small for that many nets. The
+/// same arguments always give the same board.
+pub fn generate_problem(
+    dimensions: (u32, u32),
+    nets: usize,
+    distribution: PinDistribution,
+    seed: u64,
+) -> Option<Problem> {
+    if dimensions.0 == 0 || dimensions.1 == 0 {
+        return None;
+    }
+    let mut random = StdRng::seed_from_u64(seed);
+    for _ in 0..MAX_ATTEMPTS {
+        let pins = match place_pins(dimensions, nets, distribution, &mut random) {
+            Some(pins) => pins,
+            None => continue,
+        };
+
+        let builder = pins
+            .iter()
+            .fold(ProblemBuilder::new(dimensions), |builder, (start, end)| {
+                builder.net(*start, *end)
+            });
+        if let Ok(problem) = builder.build() {
+            return Some(problem);
+        }
+    }
+    None
+}
+
+/// Pin pairs of one candidate board, or `None` when some net couldn't be placed and routed.
+fn place_pins(
+    dimensions: (u32, u32),
+    nets: usize,
+    distribution: PinDistribution,
+    random: &mut StdRng,
+) -> Option<Vec<PinPair>> {
+    let (rows, columns) = dimensions;
+    let pair: Box<dyn Fn(&mut StdRng) -> PinPair> = match distribution {
+        PinDistribution::Uniform => Box::new(move |random| {
+            let mut pick = || (random.gen_range(0..rows), random.gen_range(0..columns));
+            (pick(), pick())
+        }),
+        PinDistribution::Clustered { clusters, spread } => {
+            let centres: Vec<(u32, u32)> = (0..clusters.max(1))
+                .map(|_| (random.gen_range(0..rows), random.gen_range(0..columns)))
+                .collect();
+            Box::new(move |random| {
+                let mut pick = || {
+                    let centre = *centres.choose(random).unwrap();
+                    let row = random.gen_range(
+                        centre.0.saturating_sub(spread)..=(centre.0 + spread).min(rows - 1),
+                    );
+                    let column = random.gen_range(
+                        centre.1.saturating_sub(spread)..=(centre.1 + spread).min(columns - 1),
+                    );
+                    (row, column)
+                };
+                (pick(), pick())
+            })
+        }
+        PinDistribution::Bus => {
+            let vertical = random.gen::<bool>();
+            let (length, width) = if vertical {
+                (rows, columns)
+            } else {
+                (columns, rows)
+            };
+            let lanes: Vec<u32> = (1..width.saturating_sub(1)).step_by(2).collect();
+            if lanes.is_empty() {
+                return None;
+            }
+            Box::new(move |random| {
+                let lane = *lanes.choose(random).unwrap();
+                let (start, end) = (random.gen_range(0..length), random.gen_range(0..length));
+                if vertical {
+                    ((start, lane), (end, lane))
+                } else {
+                    ((lane, start), (lane, end))
+                }
+            })
+        }
+    };
+
+    let mut blocked = vec![false; rows as usize * columns as usize];
+    let mut pins = vec![];
+    for _ in 0..nets {
+        let routed = (0..MAX_DRAWS).find_map(|_| {
+            let (start, end) = pair(random);
+            if start == end || blocked[cell(start, dimensions)] || blocked[cell(end, dimensions)] {
+                return None;
+            }
+            blocked[cell(start, dimensions)] = true;
+            blocked[cell(end, dimensions)] = true;
+            match wavefront(start, end, dimensions, &blocked) {
+                Some(path) => Some(((start, end), path)),
+                None => {
+                    blocked[cell(start, dimensions)] = false;
+                    blocked[cell(end, dimensions)] = false;
+                    None
+                }
+            }
+        })?;
+        for point in routed.1 {
+            blocked[cell(point, dimensions)] = true;
+        }
+        pins.push(routed.0);
+    }
+    Some(pins)
+}
+
+#[cfg(test)]
+mod test;
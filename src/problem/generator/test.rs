@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn test_generated_problems_are_solvable() {
+    for (dimensions, nets, distribution) in IntoIterator::into_iter([
+        ((16, 16), 8, PinDistribution::Uniform),
+        (
+            (16, 16),
+            8,
+            PinDistribution::Clustered {
+                clusters: 3,
+                spread: 3,
+            },
+        ),
+        ((32, 16), 10, PinDistribution::Bus),
+    ]) {
+        let problem = generate_problem(dimensions, nets, distribution, 11).unwrap();
+        assert_eq!(problem.dimensions(), dimensions);
+        assert_eq!(problem.pin_locations().len(), nets);
+        let again = generate_problem(dimensions, nets, distribution, 11).unwrap();
+        assert_eq!(problem.pin_locations(), again.pin_locations());
+
+        let lee = problem.lee_route();
+        let pathfinder = problem.pathfinder_route(&Default::default());
+        assert!(lee.collisions() == 0 || pathfinder.individual.collisions() == 0);
+    }
+    assert!(generate_problem((2, 2), 3, PinDistribution::Uniform, 0).is_none());
+}
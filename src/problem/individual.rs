@@ -1,34 +1,126 @@
 use Direction::*;
 
+use super::diversity::cells_distance;
+use super::mutation::Targeting;
+use super::occupancy::{
+    new_routes, with_occupancy, with_tracked_occupancy, OccupancyCounts, RoutesVersion,
+};
 use colored::*;
+use rand::distributions::WeightedIndex;
+use rand::seq::SliceRandom;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
 
-const WEIGHTS: (f32, f32, f32) = (100., 0.2, 0.1);
+pub(crate) const WEIGHTS: (f32, f32, f32) = (100., 0.2, 0.1);
 
 const COLLISION_FACTOR: f32 = 0.1;
 const SIDE_FACTOR: f32 = 0.;
 const STEP_BONUS: f32 = 0.5;
 const BASE: f32 = 1.;
+const SEGMENT_TARGET_BASE: f32 = 0.1;
 
-#[derive(Clone, Debug)]
+/// The two pins of a net, as `(row, column)` cells.
+pub type PinPair = ((u32, u32), (u32, u32));
+
+/// Candidate routing of a whole board, one `Connection` per net.
+#[derive(Clone, Debug, Serialize)]
 pub struct Individual {
+    /// Once `metrics` is tracked, connections must only change through `update_connection`;
+    /// writing to them directly leaves the cached fitness stale.
     connections: Vec<Connection>,
     dimensions: (u32, u32),
+    /// Cells no trace should use, each counted as one more trace when collisions are counted.
+    #[serde(skip)]
+    obstacles: Arc<[(u32, u32)]>,
+    #[serde(skip)]
+    metrics: Option<Metrics>,
+}
+
+/// Terms of `Individual::evaluate` and their weighted sum, lower is better.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct FitnessBreakdown {
+    /// See `Individual::collisions`.
+    pub collisions: u32,
+    /// See `Individual::total_length`.
+    pub length: u32,
+    /// See `Individual::segment_count`.
+    pub segments: usize,
+    /// Weighted sum of the terms.
+    pub total: f32,
+}
+
+impl FitnessBreakdown {
+    /// Name, raw value and weighted contribution to `total` of every term.
+    pub fn terms(&self) -> Vec<(&'static str, f32, f32)> {
+        vec![
+            (
+                "collisions",
+                self.collisions as f32,
+                self.collisions as f32 * WEIGHTS.0,
+            ),
+            ("length", self.length as f32, self.length as f32 * WEIGHTS.1),
+            (
+                "segments",
+                self.segments as f32,
+                self.segments as f32 * WEIGHTS.2,
+            ),
+        ]
+    }
 }
 
+impl fmt::Display for FitnessBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<String> = self
+            .terms()
+            .iter()
+            .map(|(name, value, weighted)| format!("{} {} ({:.1})", name, value, weighted))
+            .collect();
+        write!(f, "{} = {:.1}", terms.join(" + "), self.total)
+    }
+}
+
+/// Cached fitness terms, updated per connection whenever one of them changes.
+#[derive(Clone, Debug)]
+struct Metrics {
+    lengths: Vec<u32>,
+    segments: Vec<usize>,
+    length: u32,
+    segment_number: usize,
+    collisions: u32,
+    /// Version of the routes the counts tracked by `with_tracked_occupancy` need to match.
+    routes: RoutesVersion,
+}
+
+/// Routes every pin pair with a biased random walk, the way the GA's initial population is built.
+/// The same `seed` always gives the same individual.
 pub fn generate_individual(
     dimensions: (u32, u32),
     pin_locations: Vec<((u32, u32), (u32, u32))>,
     seed: Option<u64>,
+) -> Individual {
+    generate_individual_with_obstacles(dimensions, pin_locations, Vec::new().into(), seed)
+}
+
+/// Like `generate_individual`, with the random walks steering clear of `obstacles`.
+pub fn generate_individual_with_obstacles(
+    dimensions: (u32, u32),
+    pin_locations: Vec<((u32, u32), (u32, u32))>,
+    obstacles: Arc<[(u32, u32)]>,
+    seed: Option<u64>,
 ) -> Individual {
     let mut individual = Individual {
         connections: Vec::new(),
         dimensions,
+        obstacles,
+        metrics: None,
     };
 
     let mut point_map = vec![vec![false; dimensions.1 as usize]; dimensions.0 as usize];
+    for obstacle in individual.obstacles.clone().iter() {
+        individual.mark_point(*obstacle, true, &mut point_map);
+    }
 
     for pin_pair in &pin_locations {
         individual.mark_point(pin_pair.0, true, &mut point_map);
@@ -41,22 +133,42 @@ pub fn generate_individual(
         individual.connections.push(connection);
     }
 
+    individual.track_metrics();
     individual
 }
 
 impl Individual {
+    /// Empty individual without any connections.
     pub fn new() -> Individual {
         Individual {
             connections: vec![],
             dimensions: (0, 0),
+            obstacles: Vec::new().into(),
+            metrics: None,
         }
     }
 
-    fn find_neighbors(&self, point: (u32, u32), point_map: &Vec<Vec<bool>>) -> [f32; 4] {
+    /// Builds an individual from one path of adjacent cells per net, leading from its first pin
+    /// to its second.
+    pub fn from_paths(dimensions: (u32, u32), paths: &[Vec<(u32, u32)>]) -> Individual {
+        let mut individual = Individual {
+            connections: paths
+                .iter()
+                .map(|path| Connection::from_points(path))
+                .collect(),
+            dimensions,
+            obstacles: Vec::new().into(),
+            metrics: None,
+        };
+        individual.track_metrics();
+        individual
+    }
+
+    fn find_neighbors(&self, point: (u32, u32), point_map: &[Vec<bool>]) -> [f32; 4] {
         // Up, DOWN, RIGHT, LEFT
         let mut neighbors: [f32; 4] = [1.0; 4];
 
-        if point.0 <= 0 {
+        if point.0 == 0 {
             neighbors[0] = SIDE_FACTOR;
         } else if point_map[point.0 as usize - 1][point.1 as usize] {
             neighbors[0] = COLLISION_FACTOR;
@@ -71,7 +183,7 @@ impl Individual {
         } else if point_map[point.0 as usize][point.1 as usize + 1] {
             neighbors[2] = COLLISION_FACTOR;
         }
-        if point.1 <= 0 {
+        if point.1 == 0 {
             neighbors[3] = SIDE_FACTOR;
         } else if point_map[point.0 as usize][point.1 as usize - 1] {
             neighbors[3] = COLLISION_FACTOR;
@@ -84,7 +196,7 @@ impl Individual {
         &mut self,
         pins: ((u32, u32), (u32, u32)),
         seed: Option<u64>,
-        point_map: &mut Vec<Vec<bool>>,
+        point_map: &mut [Vec<bool>],
     ) -> Connection {
         let mut connection = Connection {
             start: pins.0,
@@ -100,13 +212,10 @@ impl Individual {
 
         let mut probabilities = self.find_neighbors(connection.start, point_map);
 
-        let mut prob_sum = 0.;
-        for i in 0..probabilities.len() {
-            prob_sum += probabilities[i]
-        }
+        let prob_sum: f32 = probabilities.iter().sum();
 
-        for i in 0..probabilities.len() {
-            probabilities[i] /= prob_sum;
+        for probability in probabilities.iter_mut() {
+            *probability /= prob_sum;
         }
 
         let mut random = match seed {
@@ -143,80 +252,406 @@ impl Individual {
         connection
     }
 
-    fn mark_point(&mut self, point: (u32, u32), val: bool, point_map: &mut Vec<Vec<bool>>) {
+    fn mark_point(&mut self, point: (u32, u32), val: bool, point_map: &mut [Vec<bool>]) {
         point_map[point.0 as usize][point.1 as usize] = val;
     }
 
-    fn collisions(&self) -> u32 {
-        // println!("start");
-        let mut points: HashMap<(u32, u32), bool> = HashMap::new();
-        let mut collisions = 0;
-        for point in self.collect_points() {
-            // println!("Punkt hashmapa {:?}", point);
-            if points.contains_key(&point) {
-                collisions += 1;
-            } else {
-                points.insert(point, true);
+    /// Occurrences of cells beyond the first, counting traces crossing themselves.
+    pub fn collisions(&self) -> u32 {
+        if let Some(metrics) = &self.metrics {
+            return metrics.collisions;
+        }
+
+        with_occupancy(self.dimensions, |grid| {
+            for obstacle in self.obstacles.iter() {
+                grid.occupy(*obstacle);
             }
+            let mut collisions = 0;
+            for connection in &self.connections {
+                connection.for_each_point(|point| {
+                    if grid.occupy(point) {
+                        collisions += 1;
+                    }
+                });
+            }
+            collisions
+        })
+    }
+
+    /// Steps of all connections together.
+    pub fn total_length(&self) -> u32 {
+        match &self.metrics {
+            Some(metrics) => metrics.length,
+            None => self.connections.iter().map(Connection::length).sum(),
         }
+    }
 
-        collisions
+    /// Straight segments of all connections together.
+    pub fn segment_count(&self) -> usize {
+        match &self.metrics {
+            Some(metrics) => metrics.segment_number,
+            None => self
+                .connections
+                .iter()
+                .map(|connection| connection.segments.len())
+                .sum(),
+        }
     }
 
-    fn collect_points(&self) -> Vec<(u32, u32)> {
-        let mut points = vec![];
-        if self.connections.len() > 0 {
-            for i in 0..self.connections.len() {
-                points.append(&mut self.connections[i].following_points());
+    /// Weighted fitness minimised by the searches, see `fitness` for its terms.
+    pub fn evaluate(&self) -> f32 {
+        self.fitness().total
+    }
+
+    /// Every term of `evaluate` next to the weighted total.
+    pub fn fitness(&self) -> FitnessBreakdown {
+        let (collisions, length, segments) =
+            (self.collisions(), self.total_length(), self.segment_count());
+        FitnessBreakdown {
+            collisions,
+            length,
+            segments,
+            total: collisions as f32 * WEIGHTS.0
+                + length as f32 * WEIGHTS.1
+                + segments as f32 * WEIGHTS.2,
+        }
+    }
+
+    /// Corner points of every connection, from its first pin to its second.
+    pub fn polylines(&self) -> Vec<Vec<(u32, u32)>> {
+        self.connections
+            .iter()
+            .map(|connection| {
+                let mut corner = connection.start;
+                let mut corners = vec![corner];
+                for segment in &connection.segments {
+                    for _ in 0..segment.length {
+                        corner = move_direction(corner, segment.direction);
+                    }
+                    corners.push(corner);
+                }
+                corners
+            })
+            .collect()
+    }
+
+    /// Builds the metrics cache from scratch so later changes can be scored incrementally.
+    fn track_metrics(&mut self) {
+        self.metrics = None;
+        let lengths: Vec<u32> = self.connections.iter().map(Connection::length).collect();
+        let segments: Vec<usize> = self
+            .connections
+            .iter()
+            .map(|connection| connection.segments.len())
+            .collect();
+        self.metrics = Some(Metrics {
+            length: lengths.iter().sum(),
+            segment_number: segments.iter().sum(),
+            lengths,
+            segments,
+            collisions: self.collisions(),
+            routes: new_routes(),
+        });
+    }
+
+    /// Applies `change` to one connection, re-scoring only that connection.
+    fn update_connection(&mut self, index: usize, change: impl FnOnce(&mut Connection)) {
+        let (obstacles, connections) = (&self.obstacles, &mut self.connections);
+        let metrics = match &mut self.metrics {
+            Some(metrics) => metrics,
+            None => return change(&mut connections[index]),
+        };
+        let next = new_routes();
+        with_tracked_occupancy(metrics.routes, next, self.dimensions, |occupancy, fresh| {
+            if fresh {
+                fill_occupancy(occupancy, obstacles, connections);
             }
+            metrics.remove(index, &connections[index], occupancy);
+            change(&mut connections[index]);
+            metrics.add(index, &connections[index], occupancy);
+        });
+        metrics.routes = next;
+    }
+
+    /// Places the individual on a board with `obstacles`, which then count towards its collisions.
+    pub fn with_obstacles(mut self, obstacles: Arc<[(u32, u32)]>) -> Individual {
+        self.obstacles = obstacles;
+        if self.metrics.is_some() {
+            self.track_metrics();
         }
-        // println!("---");
+        self
+    }
 
-        points
+    /// Drops the metrics cache, so the individual is scored from scratch every time it is
+    /// evaluated.
+    pub fn untracked(mut self) -> Individual {
+        self.metrics = None;
+        self
     }
 
-    pub fn evaluate(&self) -> f32 {
-        let mut connection_length: u32 = 0;
-        let mut segment_number = 0;
+    /// Cells no trace may use, counted as collisions when one does.
+    pub fn obstacles(&self) -> &[(u32, u32)] {
+        &self.obstacles
+    }
+
+    /// Routed connections, in the order of the problem's pin pairs.
+    pub fn connections(&self) -> &[Connection] {
+        &self.connections
+    }
+
+    /// Rows and columns of the board.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    /// Number of nets.
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Number of cells of every connection that are shared with some other trace.
+    pub fn connection_collisions(&self) -> Vec<u32> {
+        self.with_occupancy(|occupancy| {
+            self.connections
+                .iter()
+                .map(|connection| {
+                    let mut collisions = 0;
+                    connection.for_each_point(|point| {
+                        if occupancy.count(point) > 1 {
+                            collisions += 1;
+                        }
+                    });
+                    collisions
+                })
+                .collect()
+        })
+    }
+
+    /// Cells used by more than one trace.
+    pub fn collision_cells(&self) -> Vec<(u32, u32)> {
+        self.with_occupancy(|occupancy| {
+            let mut cells = vec![];
+            for row in 0..self.dimensions.0 {
+                for column in 0..self.dimensions.1 {
+                    if occupancy.count((row, column)) > 1 {
+                        cells.push((row, column));
+                    }
+                }
+            }
+            cells
+        })
+    }
+
+    /// Sorted, deduplicated cell indices covered by every net, the representation compared by
+    /// `diversity::cells_distance`.
+    pub fn net_cells(&self) -> Vec<Vec<u32>> {
+        self.connections
+            .iter()
+            .map(|connection| {
+                let mut cells = vec![];
+                connection
+                    .for_each_point(|point| cells.push(point.0 * self.dimensions.1 + point.1));
+                cells.sort_unstable();
+                cells.dedup();
+                cells
+            })
+            .collect()
+    }
 
-        for connection in self.connections.as_slice() {
-            for segment in connection.segments.as_slice() {
-                connection_length += segment.length as u32;
+    /// Structural distance to `other`, see `diversity::cells_distance`.
+    pub fn distance(&self, other: &Individual) -> f32 {
+        cells_distance(&self.net_cells(), &other.net_cells())
+    }
+
+    /// Runs `f` with the number of traces on every cell, obstacles counting as one.
+    fn with_occupancy<T>(&self, f: impl FnOnce(&OccupancyCounts) -> T) -> T {
+        match &self.metrics {
+            Some(metrics) => {
+                let routes = metrics.routes;
+                with_tracked_occupancy(routes, routes, self.dimensions, |occupancy, fresh| {
+                    if fresh {
+                        fill_occupancy(occupancy, &self.obstacles, &self.connections);
+                    }
+                    f(occupancy)
+                })
+            }
+            None => {
+                let mut occupancy = OccupancyCounts::new(self.dimensions);
+                fill_occupancy(&mut occupancy, &self.obstacles, &self.connections);
+                f(&occupancy)
             }
-            segment_number += connection.segments.len();
         }
+    }
 
-        self.collisions() as f32 * WEIGHTS.0
-            + connection_length as f32 * WEIGHTS.1
-            + segment_number as f32 * WEIGHTS.2
+    /// Picks the segment of a connection to mutate, preferring segments running through collision
+    /// cells when `targeting` asks for it.
+    fn pick_segment(&self, index: usize, targeting: Targeting, random: &mut StdRng) -> usize {
+        let connection = &self.connections[index];
+        if targeting == Targeting::Collisions {
+            let weights = self.with_occupancy(|occupancy| connection.segment_collisions(occupancy));
+            if weights.iter().any(|&collisions| collisions > 0) {
+                let choice = WeightedIndex::new(
+                    weights
+                        .iter()
+                        .map(|&collisions| collisions as f32 + SEGMENT_TARGET_BASE),
+                )
+                .unwrap();
+                return random.sample(choice);
+            }
+        }
+        random.gen_range(0..connection.segments.len())
+    }
+
+    /// Replaces the connection at `index` with the one `other` routes for the same net.
+    pub fn inherit(&mut self, other: &Self, index: usize) {
+        let replacement = other.connections[index].clone();
+        self.update_connection(index, |connection| *connection = replacement);
     }
 
+    /// Puts `connection` back in place of the connection at `index`, e.g. to undo a mutation.
+    pub fn restore_connection(&mut self, index: usize, connection: Connection) {
+        self.update_connection(index, |current| *current = connection);
+    }
+
+    /// Takes the net at `roll` of the way through the net list from `other`.
     pub fn crossover(&mut self, other: &Self, roll: f32) {
         let index = (roll * self.connections.len() as f32) as usize;
-        self.connections[index] = other.connections[index].clone();
+        self.inherit(other, index);
     }
 
+    /// Shifts a segment of every connection with probability `mutation_chance`.
     pub fn mutate(&mut self, random: &mut StdRng, mutation_chance: f32) {
-        for connection in &mut self.connections {
+        for index in 0..self.connections.len() {
             if random.gen::<f32>() < mutation_chance {
-                connection.mutate_segment(
-                    (random.gen::<f32>(), random.gen::<f32>()),
-                    (self.dimensions.0 as u32, self.dimensions.1 as u32),
-                )
+                self.shift_segment(index, Targeting::Uniform, random);
+            }
+        }
+    }
+
+    /// Pushes a whole segment of the connection sideways.
+    pub fn shift_segment(&mut self, index: usize, targeting: Targeting, random: &mut StdRng) {
+        let roll = (random.gen::<f32>(), random.gen::<f32>());
+        let dimensions = self.dimensions;
+        match targeting {
+            Targeting::Uniform => self.update_connection(index, |connection| {
+                connection.mutate_segment(roll, dimensions)
+            }),
+            Targeting::Collisions => {
+                let segment = self.pick_segment(index, targeting, random);
+                self.update_connection(index, |connection| {
+                    connection.shift_segment(segment, roll, dimensions)
+                })
+            }
+        }
+    }
+
+    /// Pushes the tail of a segment sideways, turning it into a detour.
+    pub fn split_segment(&mut self, index: usize, targeting: Targeting, random: &mut StdRng) {
+        let dimensions = self.dimensions;
+        let segment = self.pick_segment(index, targeting, random);
+        self.update_connection(index, |connection| {
+            connection.split_segment(segment, random, dimensions)
+        });
+    }
+
+    /// Removes a U-turn of the connection, or pulls a corner in when there is none.
+    pub fn straighten(&mut self, index: usize, random: &mut StdRng) {
+        self.update_connection(index, |connection| connection.straighten(random));
+    }
+
+    /// Replaces the part of the connection between two random cells with a fresh random walk.
+    pub fn reroute(&mut self, index: usize, random: &mut StdRng) {
+        let points = self.connections[index].following_points();
+        let from = random.gen_range(0..points.len() - 1);
+        let to = random.gen_range(from + 1..points.len());
+
+        let mut path = points[..=from].to_vec();
+        if points[from] != points[to] {
+            let mut point_map = self.point_map_without(index);
+            let detour = self.random_walk(
+                (points[from], points[to]),
+                Some(random.gen()),
+                &mut point_map,
+            );
+            path.extend(detour.following_points().into_iter().skip(1));
+        }
+        path.extend_from_slice(&points[to + 1..]);
+
+        let rerouted = Connection::from_points(&path);
+        self.update_connection(index, |connection| *connection = rerouted);
+    }
+
+    /// Routes the connection again from scratch, avoiding the cells of all other connections.
+    pub fn regenerate(&mut self, index: usize, random: &mut StdRng) {
+        let pins = (self.connections[index].start, self.connections[index].end);
+        let mut point_map = self.point_map_without(index);
+        self.mark_point(pins.0, true, &mut point_map);
+        let regenerated = self.random_walk(pins, Some(random.gen()), &mut point_map);
+        self.update_connection(index, |connection| *connection = regenerated);
+    }
+
+    fn point_map_without(&self, index: usize) -> Vec<Vec<bool>> {
+        let mut point_map =
+            vec![vec![false; self.dimensions.1 as usize]; self.dimensions.0 as usize];
+        for obstacle in self.obstacles.iter() {
+            point_map[obstacle.0 as usize][obstacle.1 as usize] = true;
+        }
+        for (other, connection) in self.connections.iter().enumerate() {
+            if other != index {
+                connection
+                    .for_each_point(|point| point_map[point.0 as usize][point.1 as usize] = true);
             }
         }
+        point_map
+    }
+}
+
+impl Metrics {
+    fn add(&mut self, index: usize, connection: &Connection, occupancy: &mut OccupancyCounts) {
+        self.lengths[index] = connection.length();
+        self.segments[index] = connection.segments.len();
+        self.length += self.lengths[index];
+        self.segment_number += self.segments[index];
+        connection.for_each_point(|point| occupancy.add(point));
+        self.collisions = occupancy.collisions();
+    }
+
+    fn remove(&mut self, index: usize, connection: &Connection, occupancy: &mut OccupancyCounts) {
+        self.length -= self.lengths[index];
+        self.segment_number -= self.segments[index];
+        connection.for_each_point(|point| occupancy.remove(point));
+    }
+}
+
+/// Counts the obstacles and every cell of `connections` into empty `occupancy`.
+fn fill_occupancy(
+    occupancy: &mut OccupancyCounts,
+    obstacles: &[(u32, u32)],
+    connections: &[Connection],
+) {
+    for obstacle in obstacles {
+        occupancy.add(*obstacle);
+    }
+    for connection in connections {
+        connection.for_each_point(|point| occupancy.add(point));
+    }
+}
+
+impl Default for Individual {
+    fn default() -> Self {
+        Individual::new()
     }
 }
 
 impl fmt::Display for Individual {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fn match_dir(dir: Direction, symbols: (char, char, char, char)) -> String {
-            String::from(match dir {
+            match dir {
                 North => String::from(symbols.0),
                 South => String::from(symbols.1),
                 East => String::from(symbols.2),
                 West => String::from(symbols.3),
-            })
+            }
         }
 
         let mut character_map =
@@ -225,6 +660,11 @@ impl fmt::Display for Individual {
                 self.dimensions.0 as usize
             ];
 
+        for obstacle in self.obstacles.iter() {
+            character_map[obstacle.0 as usize][obstacle.1 as usize] =
+                String::from('\u{2588}').color("white");
+        }
+
         let color = ["red", "green", "yellow", "blue", "magenta", "cyan", "white"];
 
         for c in 0..self.connections.len() {
@@ -308,20 +748,28 @@ impl fmt::Display for Individual {
             .color(color[c % color.len()]);
         }
 
-        Ok(for i in character_map {
-            for j in i {
-                write!(f, "{}", &j).expect("Fail");
+        for row in character_map {
+            for cell in row {
+                write!(f, "{}", &cell)?;
             }
-            write!(f, "\n").expect("Fail");
-        })
+            writeln!(f)?;
+        }
+
+        writeln!(f, "{}", self.fitness())
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Direction {
+/// Heading of a `Segment`. Points are `(row, column)`, so `North` decreases the row and `East`
+/// increases the column.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// Towards row 0.
     North,
+    /// Towards the last row.
     South,
+    /// Towards the last column.
     East,
+    /// Towards column 0.
     West,
 }
 
@@ -331,14 +779,18 @@ impl Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Segment {
-    length: u32,
-    direction: Direction,
+/// Straight run of `length` cells in `direction`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Segment {
+    /// Cells moved, not counting the one the segment starts on.
+    pub length: u32,
+    /// Heading of every step of the segment.
+    pub direction: Direction,
 }
 
-#[derive(Debug, Clone)]
-struct Connection {
+/// Trace of one net: a chain of segments leading from the `start` pin to the `end` pin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Connection {
     start: (u32, u32),
     end: (u32, u32),
     segments: Vec<Segment>,
@@ -351,7 +803,7 @@ impl Connection {
         individual: &mut Individual,
         actual_point: &mut (u32, u32),
         random: &mut StdRng,
-        point_map: &mut Vec<Vec<bool>>,
+        point_map: &mut [Vec<bool>],
     ) -> (Segment, Option<Direction>) {
         let mut segment = Segment {
             length: 1,
@@ -417,59 +869,89 @@ impl Connection {
         (segment, None)
     }
 
-    fn following_points(&self) -> Vec<(u32, u32)> {
-        fn segment_to_points(
-            actual_point: (u32, u32),
-            length: u32,
-            direction: Direction,
-        ) -> Vec<(u32, u32)> {
-            let mut points = vec![];
-            for i in 0..length {
-                let actual_point = match direction {
-                    North => (actual_point.0 - i, actual_point.1),
-                    South => (actual_point.0 + i, actual_point.1),
-                    East => (actual_point.0, actual_point.1 + i),
-                    West => (actual_point.0, actual_point.1 - i),
-                };
-                points.push(actual_point);
+    /// First pin of the net.
+    pub fn start(&self) -> (u32, u32) {
+        self.start
+    }
+
+    /// Second pin of the net, where the last segment ends.
+    pub fn end(&self) -> (u32, u32) {
+        self.end
+    }
+
+    /// Straight runs leading from `start` to `end`.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Number of steps from `start` to `end`.
+    pub fn length(&self) -> u32 {
+        self.segments.iter().map(|segment| segment.length).sum()
+    }
+
+    /// Builds a connection walking through `points`, each adjacent to the previous one.
+    fn from_points(points: &[(u32, u32)]) -> Connection {
+        let mut connection = Connection {
+            start: points[0],
+            end: points[points.len() - 1],
+            segments: Vec::new(),
+        };
+
+        for step in points.windows(2) {
+            if step[0] == step[1] {
+                continue;
+            }
+            let direction = step_direction(step[0], step[1]);
+            match connection.segments.last_mut() {
+                Some(segment) if segment.direction == direction => segment.length += 1,
+                _ => connection.segments.push(Segment {
+                    length: 1,
+                    direction,
+                }),
             }
-            // println!("SEGMETNY: {:?}", points);
-            points
         }
 
-        let mut points: Vec<(u32, u32)> = vec![];
-        let mut current_point: (u32, u32) = self.start.clone();
-        for segment in self.segments.as_slice() {
-            let mut segment_points;
-            match segment.direction {
-                North => {
-                    segment_points = segment_to_points(current_point, segment.length, North);
-                    current_point = (current_point.0 - segment.length, current_point.1);
-                }
-                South => {
-                    segment_points = segment_to_points(current_point, segment.length, South);
-                    current_point = (current_point.0 + segment.length, current_point.1);
-                }
-                East => {
-                    segment_points = segment_to_points(current_point, segment.length, East);
-                    current_point = (current_point.0, current_point.1 + segment.length);
-                }
-                West => {
-                    segment_points = segment_to_points(current_point, segment.length, West);
-                    current_point = (current_point.0, current_point.1 - segment.length);
-                }
+        connection.normalize();
+        connection
+    }
 
-            };
-            points.append(&mut segment_points);
-        }
-        points.push(self.end);
+    /// Number of collision cells every segment runs through, corners included.
+    fn segment_collisions(&self, occupancy: &OccupancyCounts) -> Vec<u32> {
+        let mut point = self.start;
+        self.segments
+            .iter()
+            .map(|segment| {
+                let mut collisions = (occupancy.count(point) > 1) as u32;
+                for _ in 0..segment.length {
+                    point = move_direction(point, segment.direction);
+                    collisions += (occupancy.count(point) > 1) as u32;
+                }
+                collisions
+            })
+            .collect()
+    }
 
+    /// Every cell of the connection in order, both pins included.
+    pub fn following_points(&self) -> Vec<(u32, u32)> {
+        let mut points: Vec<(u32, u32)> = vec![];
+        self.for_each_point(|point| points.push(point));
         points
     }
 
+    /// Visits every cell of the connection in order, without allocating.
+    fn for_each_point(&self, mut f: impl FnMut((u32, u32))) {
+        let mut current_point: (u32, u32) = self.start;
+        for segment in self.segments.as_slice() {
+            for _ in 0..segment.length {
+                f(current_point);
+                current_point = move_direction(current_point, segment.direction);
+            }
+        }
+        f(self.end);
+    }
 
     fn find_point(&self, index: usize) -> (u32, u32) {
-        let mut point = self.start.clone();
+        let mut point = self.start;
         for i in 0..(index + 1) {
             match self.segments[i].direction {
                 North => {
@@ -496,7 +978,7 @@ impl Connection {
         for i in 1..self.segments.len() {
             // println!("Stary {:?}", self.segments);
             // println!("Nowy {:?}", new_segments);
-            if new_segments.len() == 0 {
+            if new_segments.is_empty() {
                 new_segments.push(self.segments[i]);
             } else if new_segments.len() == 1 && new_segments[0].length == 0 {
                 new_segments.pop();
@@ -525,11 +1007,95 @@ impl Connection {
         self.segments = new_segments;
     }
 
-    fn split_segment() {
+    /// Drops empty segments and merges or cancels out neighbouring parallel ones. Every operator
+    /// that edits segments ends with this, so no connection keeps a segment of length 0.
+    fn normalize(&mut self) {
+        self.segments.retain(|segment| segment.length > 0);
+        if !self.segments.is_empty() {
+            self.flatten();
+        }
+    }
+
+    fn split_segment(&mut self, index: usize, random: &mut StdRng, dimensions: (u32, u32)) {
+        let mutant = self.segments[index];
+        let split = random.gen_range(0..mutant.length);
+        let side = match (mutant.direction, random.gen::<bool>()) {
+            (North, true) | (South, true) => East,
+            (North, false) | (South, false) => West,
+            (East, true) | (West, true) => North,
+            (East, false) | (West, false) => South,
+        };
+
+        let room = free_space(self.find_point(index), side, dimensions);
+        if room == 0 {
+            return;
+        }
+        let depth = random.gen_range(1..=room);
+
+        let detour = [
+            Segment {
+                length: split,
+                direction: mutant.direction,
+            },
+            Segment {
+                length: depth,
+                direction: side,
+            },
+            Segment {
+                length: mutant.length - split,
+                direction: mutant.direction,
+            },
+            Segment {
+                length: depth,
+                direction: invert_direction(side),
+            },
+        ];
+        self.segments
+            .splice(index..index + 1, detour.iter().copied());
+        self.normalize();
+    }
+
+    fn straighten(&mut self, random: &mut StdRng) {
+        self.normalize();
+
+        let corners: Vec<usize> = (0..self.segments.len().saturating_sub(2))
+            .filter(|&i| {
+                let (first, last) = (self.segments[i].direction, self.segments[i + 2].direction);
+                first == last || first == invert_direction(last)
+            })
+            .collect();
+        let u_turns: Vec<usize> = corners
+            .iter()
+            .copied()
+            .filter(|&i| self.segments[i].direction != self.segments[i + 2].direction)
+            .collect();
+
+        let index = match u_turns.choose(random).or_else(|| corners.choose(random)) {
+            Some(&index) => index,
+            None => return,
+        };
+
+        if self.segments[index].direction == self.segments[index + 2].direction {
+            self.segments[index].length += self.segments[index + 2].length;
+            self.segments[index + 2].length = 0;
+        } else {
+            let overlap = self.segments[index]
+                .length
+                .min(self.segments[index + 2].length);
+            self.segments[index].length -= overlap;
+            self.segments[index + 2].length -= overlap;
+        }
+        self.normalize();
     }
 
+    /// Pushes the segment at `roll.0` of the way through the connection sideways, by a distance
+    /// drawn from `roll.1`.
     pub fn mutate_segment(&mut self, roll: (f32, f32), dimensions: (u32, u32)) {
         let index = (roll.0 * self.segments.len() as f32) as usize;
+        self.shift_segment(index, roll, dimensions);
+    }
+
+    fn shift_segment(&mut self, index: usize, roll: (f32, f32), dimensions: (u32, u32)) {
         let mutant: &Segment = &self.segments[index];
         let segment_point = self.find_point(index);
 
@@ -537,8 +1103,6 @@ impl Connection {
         let direction: Option<Direction>;
         // println!("{}", index);
 
-
-
         match mutant.direction {
             North | South => {
                 if roll.0 <= 0.5 {
@@ -593,7 +1157,7 @@ impl Connection {
 
         // println!("{:?}", self.segments);
 
-        self.flatten();
+        self.normalize();
     }
 }
 
@@ -618,6 +1182,28 @@ fn move_direction(point: (u32, u32), direction: Direction) -> (u32, u32) {
     }
 }
 
+fn step_direction(from: (u32, u32), to: (u32, u32)) -> Direction {
+    if to.0 < from.0 {
+        North
+    } else if to.0 > from.0 {
+        South
+    } else if to.1 > from.1 {
+        East
+    } else {
+        West
+    }
+}
+
+/// Number of cells between `point` and the edge of the board in `direction`.
+fn free_space(point: (u32, u32), direction: Direction, dimensions: (u32, u32)) -> u32 {
+    match direction {
+        North => point.0,
+        South => dimensions.0 - 1 - point.0,
+        East => dimensions.1 - 1 - point.1,
+        West => point.1,
+    }
+}
+
 fn distance_factor(start: (u32, u32), end: (u32, u32)) -> f32 {
     let distance =
         (end.0 as i32 - start.0 as i32).abs() as f32 + (end.1 as i32 - start.1 as i32).abs() as f32;
@@ -636,8 +1222,8 @@ fn get_probability(
 ) -> [f32; 4] {
     let mut probabilities: [f32; 4] = [BASE; 4];
 
-    for i in 0..probabilities.len() {
-        probabilities[i] *= neighbors[i];
+    for (probability, neighbor) in probabilities.iter_mut().zip(neighbors.iter()) {
+        *probability *= neighbor;
     }
 
     for dir in Direction::iterator() {
@@ -656,24 +1242,18 @@ fn get_probability(
         West => probabilities[2] = 0.0,
     };
 
-    let mut prob_sum = 0.0;
-    let mut max = 0.;
+    let max = probabilities.iter().cloned().fold(0., f32::max);
 
-    for i in 0..probabilities.len() {
-        if probabilities[i] > max {
-            max = probabilities[i];
+    for probability in probabilities.iter_mut() {
+        if *probability == max {
+            *probability += steps as f32 * STEP_BONUS;
         }
     }
 
-    for i in 0..probabilities.len() {
-        if probabilities[i] == max {
-            probabilities[i] += steps as f32 * STEP_BONUS;
-        }
-        prob_sum += probabilities[i];
-    }
+    let prob_sum: f32 = probabilities.iter().sum();
 
-    for i in 0..probabilities.len() {
-        probabilities[i] /= prob_sum;
+    for probability in probabilities.iter_mut() {
+        *probability /= prob_sum;
     }
     probabilities
 }
@@ -688,5 +1268,4 @@ fn invert_direction(direction: Direction) -> Direction {
 }
 
 #[cfg(test)]
-#[path = "test.rs"]
 mod test;
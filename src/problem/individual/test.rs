@@ -0,0 +1,371 @@
+use super::*;
+use crate::problem::crossover::{Crossover, CrossoverKind};
+use crate::problem::fixtures::assert_valid_individual;
+use crate::problem::mutation::{Mutation, MutationKind, Targeting};
+use crate::problem::router::NetOrder;
+use crate::problem::ProblemBuilder;
+use proptest::prelude::*;
+
+fn setup() -> Individual {
+    let pin_locations = vec![((1, 3), (5, 3))];
+    generate_individual((6, 6), pin_locations, Some(1))
+}
+
+#[test]
+fn test_mutation() {
+    let mut individual = setup();
+
+    println!("{}", individual);
+
+    individual.connections[0].mutate_segment((0.3, 0.7), (6, 6));
+    println!("{}", individual);
+
+    // for i in 0..100000 {
+    //     let mut pin_locations = vec![];
+    //     pin_locations.push(((1, 3), (5, 3)));
+    //     individual = generate_individual((6, 6), pin_locations, Some(i));
+    //     println!("{}", individual);
+    //     individual.connections[0].mutate_segment((0.3, 0.7), (6, 6));
+    //     println!("{}", individual);
+    //     println!("------------------------------");
+    //     individual.connections[0].following_points();
+    // }
+
+    individual.connections[0] = Connection {
+        start: (2, 2),
+        end: (5, 2),
+        segments: vec![
+            Segment {
+                length: 1,
+                direction: South,
+            },
+            Segment {
+                length: 1,
+                direction: East,
+            },
+            Segment {
+                length: 1,
+                direction: South,
+            },
+            Segment {
+                length: 1,
+                direction: West,
+            },
+            Segment {
+                length: 1,
+                direction: South,
+            },
+        ],
+    };
+        println!("{}", individual);
+        individual.connections[0].mutate_segment((0.83, 0.05), (6, 6));
+        println!("{:?}", individual.connections[0]);
+        println!("{}", individual);
+}
+
+#[test]
+fn test_find_point() {
+    let individual = setup();
+    assert_eq!(individual.connections[0].find_point(0), (1, 0));
+    assert_eq!(individual.connections[0].find_point(1), (5, 0));
+    assert_eq!(individual.connections[0].find_point(2), (5, 3));
+}
+
+fn problem_strategy() -> impl Strategy<Value = ((u32, u32), Vec<PinPair>)> {
+    (4u32..24, 4u32..24, 1usize..6).prop_flat_map(|(rows, cols, nets)| {
+        let cells = (rows * cols) as usize;
+        (
+            Just((rows, cols)),
+            proptest::sample::subsequence((0..cells).collect::<Vec<_>>(), nets * 2)
+                .prop_shuffle()
+                .prop_map(move |cells| {
+                    let point = |cell: usize| (cell as u32 / cols, cell as u32 % cols);
+                    cells
+                        .chunks(2)
+                        .map(|pair| (point(pair[0]), point(pair[1])))
+                        .collect::<Vec<_>>()
+                }),
+        )
+    })
+}
+
+proptest! {
+    #[test]
+    fn prop_generate_individual((dimensions, pins) in problem_strategy(), seed: u64) {
+        let individual = generate_individual(dimensions, pins.clone(), Some(seed));
+        assert_valid_individual(&individual, dimensions, &pins);
+    }
+
+    #[test]
+    fn prop_mutate_segment(
+        (dimensions, pins) in problem_strategy(),
+        seed: u64,
+        rolls in proptest::collection::vec((0f32..1., 0f32..1.), 1..8),
+    ) {
+        let mut individual = generate_individual(dimensions, pins.clone(), Some(seed));
+        for (index, roll) in rolls.into_iter().enumerate() {
+            let connection = index % individual.connections.len();
+            individual.connections[connection].mutate_segment(roll, dimensions);
+            assert_valid_individual(&individual, dimensions, &pins);
+        }
+    }
+
+    #[test]
+    fn prop_flatten((dimensions, pins) in problem_strategy(), seed: u64) {
+        let mut individual = generate_individual(dimensions, pins.clone(), Some(seed));
+        for connection in &mut individual.connections {
+            connection.normalize();
+        }
+        assert_valid_individual(&individual, dimensions, &pins);
+    }
+
+    #[test]
+    fn prop_crossover(
+        (dimensions, pins) in problem_strategy(),
+        seeds: (u64, u64),
+        roll in 0f32..1.,
+    ) {
+        let mut first = generate_individual(dimensions, pins.clone(), Some(seeds.0));
+        let second = generate_individual(dimensions, pins.clone(), Some(seeds.1));
+        first.crossover(&second, roll);
+        assert_valid_individual(&first, dimensions, &pins);
+    }
+
+    #[test]
+    fn prop_mutate((dimensions, pins) in problem_strategy(), seed: u64, chance in 0f32..1.) {
+        let mut individual = generate_individual(dimensions, pins.clone(), Some(seed));
+        individual.mutate(&mut StdRng::seed_from_u64(seed), chance);
+        assert_valid_individual(&individual, dimensions, &pins);
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_collisions_match_point_count((dimensions, pins) in problem_strategy(), seed: u64) {
+        let individual = generate_individual(dimensions, pins, Some(seed));
+        let mut seen = std::collections::HashSet::new();
+        let expected = individual
+            .connections
+            .iter()
+            .flat_map(|connection| connection.following_points())
+            .filter(|point| !seen.insert(*point))
+            .count();
+        prop_assert_eq!(individual.collisions() as usize, expected);
+    }
+}
+
+fn untracked(individual: &Individual) -> Individual {
+    individual.clone().untracked()
+}
+
+proptest! {
+    #[test]
+    fn prop_incremental_evaluation(
+        (dimensions, pins) in problem_strategy(),
+        seeds: (u64, u64),
+        rolls in proptest::collection::vec(0f32..1., 1..8),
+    ) {
+        let mut individual = generate_individual(dimensions, pins.clone(), Some(seeds.0));
+        let other = generate_individual(dimensions, pins, Some(seeds.1));
+        let mut random = StdRng::seed_from_u64(seeds.0);
+        prop_assert_eq!(individual.evaluate(), untracked(&individual).evaluate());
+
+        for roll in rolls {
+            individual.crossover(&other, roll);
+            individual.mutate(&mut random, roll);
+            prop_assert_eq!(individual.collisions(), untracked(&individual).collisions());
+            prop_assert_eq!(individual.evaluate(), untracked(&individual).evaluate());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn prop_clones_keep_their_own_metrics(
+        (dimensions, pins) in problem_strategy(),
+        seed: u64,
+        rolls in proptest::collection::vec(0f32..1., 1..16),
+    ) {
+        let mut original = generate_individual(dimensions, pins, Some(seed));
+        let before = original.fitness();
+        let mut clone = original.clone();
+        let mut random = StdRng::seed_from_u64(seed);
+        for roll in rolls {
+            clone.mutate(&mut random, roll);
+            prop_assert_eq!(clone.collisions(), untracked(&clone).collisions());
+        }
+        prop_assert_eq!(original.fitness(), before);
+        prop_assert_eq!(original.fitness(), untracked(&original).fitness());
+
+        // The clone took over the counts tracked for both, so the original has to refill them.
+        original.mutate(&mut random, 1.);
+        prop_assert_eq!(original.fitness(), untracked(&original).fitness());
+    }
+}
+
+fn crossover_strategy() -> impl Strategy<Value = CrossoverKind> {
+    prop_oneof![
+        Just(CrossoverKind::SingleNet),
+        Just(CrossoverKind::Uniform),
+        (1usize..6).prop_map(CrossoverKind::KPoint),
+        Just(CrossoverKind::FitnessAware),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_crossover_operators(
+        (dimensions, pins) in problem_strategy(),
+        seeds: (u64, u64),
+        operator in crossover_strategy(),
+    ) {
+        let mut child = generate_individual(dimensions, pins.clone(), Some(seeds.0));
+        let other = generate_individual(dimensions, pins.clone(), Some(seeds.1));
+        operator.crossover(&mut child, &other, &mut StdRng::seed_from_u64(seeds.0));
+        assert_valid_individual(&child, dimensions, &pins);
+        prop_assert_eq!(child.evaluate(), untracked(&child).evaluate());
+    }
+}
+
+fn mutation_strategy() -> impl Strategy<Value = MutationKind> {
+    prop_oneof![
+        Just(MutationKind::ShiftSegment),
+        Just(MutationKind::SplitSegment),
+        Just(MutationKind::Straighten),
+        Just(MutationKind::Reroute),
+        Just(MutationKind::Regenerate),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_mutation_operators(
+        (dimensions, pins) in problem_strategy(),
+        seed: u64,
+        operators in proptest::collection::vec(mutation_strategy(), 1..8),
+        targeting in prop_oneof![Just(Targeting::Uniform), Just(Targeting::Collisions)],
+    ) {
+        let mut individual = generate_individual(dimensions, pins.clone(), Some(seed));
+        let mut random = StdRng::seed_from_u64(seed);
+        for (step, operator) in operators.into_iter().enumerate() {
+            operator.mutate(&mut individual, step % pins.len(), targeting, &mut random);
+            assert_valid_individual(&individual, dimensions, &pins);
+            prop_assert_eq!(individual.evaluate(), untracked(&individual).evaluate());
+        }
+    }
+
+    #[test]
+    fn prop_restore_connection_undoes_mutation(
+        (dimensions, pins) in problem_strategy(),
+        seed: u64,
+        operator in mutation_strategy(),
+    ) {
+        let mut individual = generate_individual(dimensions, pins, Some(seed));
+        let before = (individual.polylines(), individual.fitness());
+        let previous = individual.connections()[0].clone();
+        operator.mutate(&mut individual, 0, Targeting::Uniform, &mut StdRng::seed_from_u64(seed));
+        individual.restore_connection(0, previous);
+        prop_assert_eq!((individual.polylines(), individual.fitness()), before);
+    }
+}
+
+#[test]
+fn test_straighten_removes_u_turn() {
+    let mut individual = untracked(&setup());
+    individual.connections[0] = Connection {
+        start: (1, 1),
+        end: (1, 3),
+        segments: vec![
+            Segment {
+                length: 2,
+                direction: South,
+            },
+            Segment {
+                length: 2,
+                direction: East,
+            },
+            Segment {
+                length: 2,
+                direction: North,
+            },
+        ],
+    };
+    individual.straighten(0, &mut StdRng::seed_from_u64(0));
+    assert_eq!(individual.connections[0].segments.len(), 1);
+    assert_eq!(individual.connections[0].length(), 2);
+}
+
+proptest! {
+    #[test]
+    fn prop_distance((dimensions, pins) in problem_strategy(), seed: u64, other: u64) {
+        let a = generate_individual(dimensions, pins.clone(), Some(seed));
+        let b = generate_individual(dimensions, pins, Some(other));
+        prop_assert_eq!(a.distance(&a), 0.);
+        prop_assert!((a.distance(&b) - b.distance(&a)).abs() < 1e-6);
+        prop_assert!((0. ..=1.).contains(&a.distance(&b)));
+    }
+}
+
+#[test]
+fn test_fitness_breakdown() {
+    let individual = setup();
+    let fitness = individual.fitness();
+    assert_eq!(fitness.total, individual.evaluate());
+    assert_eq!(fitness.collisions, individual.collisions());
+    assert_eq!(fitness.length, individual.total_length());
+    assert_eq!(fitness.segments, individual.segment_count());
+    let weighted: f32 = fitness.terms().iter().map(|term| term.2).sum();
+    assert!((weighted - fitness.total).abs() < 1e-3);
+    assert!(format!("{}", fitness).starts_with("collisions "));
+}
+
+#[test]
+fn test_obstacles_count_as_collisions() {
+    let problem = ProblemBuilder::new((5, 5))
+        .net((0, 2), (4, 2))
+        .obstacles((0..4).map(|column| (2, column)))
+        .build()
+        .unwrap();
+    let straight = Individual::from_paths((5, 5), &[(0..5).map(|row| (row, 2)).collect()])
+        .with_obstacles(problem.obstacles().into());
+    assert_eq!(straight.collisions(), 1);
+    assert_eq!(straight.collisions(), untracked(&straight).collisions());
+
+    let routed = problem.lee_route();
+    assert_eq!(routed.collisions(), 0);
+    assert_eq!(routed.total_length(), 8);
+    let routed = problem.astar_route(NetOrder::AsGiven);
+    assert_eq!(routed.collisions(), 0);
+}
+
+#[test]
+fn test_collision_targeting_prefers_colliding_segments() {
+    // The first net runs east, south and west; the second crosses only its southward segment.
+    let first: Vec<_> = (0..6)
+        .map(|column| (0, column))
+        .chain((1..6).map(|row| (row, 5)))
+        .chain((0..5).rev().map(|column| (5, column)))
+        .collect();
+    let second: Vec<_> = (3..8).map(|column| (3, column)).collect();
+    let individual = Individual::from_paths((8, 8), &[first, second]);
+    assert_eq!(individual.connections()[0].segments().len(), 3);
+    assert_eq!(individual.collision_cells(), vec![(3, 5)]);
+
+    let mut random = StdRng::seed_from_u64(33);
+    let draws = 3000;
+    let picks = |targeting, random: &mut StdRng| {
+        (0..draws)
+            .filter(|_| individual.pick_segment(0, targeting, random) == 1)
+            .count() as f32
+            / draws as f32
+    };
+    let uniform = picks(Targeting::Uniform, &mut random);
+    let targeted = picks(Targeting::Collisions, &mut random);
+
+    assert!(
+        (uniform - 1. / 3.).abs() < 0.05,
+        "uniform share {}",
+        uniform
+    );
+    assert!(targeted > 0.75, "targeted share {}", targeted);
+}
@@ -0,0 +1,92 @@
+//! Reading and writing boards in the text format of `test_data`: a `rows;columns` line followed
+//! by one `row;column;row;column` line with the two pins of every net, optionally followed by
+//! `;name`, and one `row;column` line per obstacle.
+
+use super::builder::ProblemBuilder;
+use super::Problem;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// Reads the board at `path` into a `Problem` seeded with `seed`. The board is checked like
+/// `ProblemBuilder::build` checks it, so files with pins or obstacles off the board, nets whose
+/// two pins are the same cell or cells used by more than one pin or obstacle are rejected, even
+/// though earlier versions loaded them.
+pub fn read_problem(path: &str, seed: Option<u64>) -> io::Result<Problem> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut builder = ProblemBuilder::new((0, 0));
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.trim().split(';').collect();
+        let (numbers, name) = match fields.len() {
+            5 => (
+                &fields[..4],
+                Some(fields[4].trim()).filter(|name| !name.is_empty()),
+            ),
+            _ => (&fields[..], None),
+        };
+        let numbers = parse_numbers(numbers).ok_or_else(|| invalid_line(index))?;
+
+        builder = match (numbers.as_slice(), name) {
+            ([rows, columns], None) if index == 0 => ProblemBuilder::new((*rows, *columns)),
+            ([row, column], None) => builder.obstacle((*row, *column)),
+            ([a, b, c, d], None) => builder.net((*a, *b), (*c, *d)),
+            ([a, b, c, d], Some(name)) => builder.named_net(name, (*a, *b), (*c, *d)),
+            _ => return Err(invalid_line(index)),
+        };
+    }
+
+    if let Some(seed) = seed {
+        builder = builder.seed(seed);
+    }
+    builder
+        .build()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Like `read_problem`, but panics when the board can't be read.
+pub fn load_problem(problem_name: &str, seed: Option<u64>) -> Problem {
+    read_problem(problem_name, seed)
+        .unwrap_or_else(|error| panic!("Failed to load {}: {}", problem_name, error))
+}
+
+/// Writes `problem` in the format `read_problem` reads.
+pub fn write_problem(problem: &Problem, mut writer: impl Write) -> io::Result<()> {
+    let (rows, columns) = problem.dimensions();
+    writeln!(writer, "{};{}", rows, columns)?;
+    for ((start, end), name) in problem.pin_locations().iter().zip(problem.net_names()) {
+        write!(writer, "{};{};{};{}", start.0, start.1, end.0, end.1)?;
+        match name {
+            Some(name) => writeln!(writer, ";{}", name)?,
+            None => writeln!(writer)?,
+        }
+    }
+    for obstacle in problem.obstacles() {
+        writeln!(writer, "{};{}", obstacle.0, obstacle.1)?;
+    }
+    Ok(())
+}
+
+/// Saves `problem` to the board file at `path`.
+pub fn save_problem(problem: &Problem, path: &str) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_problem(problem, &mut writer)?;
+    writer.flush()
+}
+
+fn parse_numbers(fields: &[&str]) -> Option<Vec<u32>> {
+    fields
+        .iter()
+        .map(|field| field.trim().parse().ok())
+        .collect()
+}
+
+fn invalid_line(index: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Wrong test data format on line {}", index + 1),
+    )
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,21 @@
+use super::*;
+
+#[test]
+fn test_problem_text_round_trip() {
+    let problem = ProblemBuilder::new((8, 6))
+        .named_net("clock", (1, 1), (6, 4))
+        .net((0, 5), (7, 0))
+        .obstacle((3, 3))
+        .build()
+        .unwrap();
+    let path = std::env::temp_dir().join("ga_pcb_round_trip.txt");
+    let path = path.to_str().unwrap();
+    save_problem(&problem, path).unwrap();
+    let loaded = read_problem(path, None).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded.dimensions(), problem.dimensions());
+    assert_eq!(loaded.pin_locations(), problem.pin_locations());
+    assert_eq!(loaded.net_names(), problem.net_names());
+    assert_eq!(loaded.obstacles(), problem.obstacles());
+}
@@ -0,0 +1,199 @@
+//! Island-model GA: sub-populations evolving in parallel and exchanging their best individuals.
+
+use super::result::{EpochStats, SearchConfig, SearchHistory, SearchResult};
+use super::{FnType, Individual, Problem};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::thread;
+use std::time::Instant;
+
+/// Which islands send their best individuals to which.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Topology {
+    /// Island `i` sends to island `i + 1`, the last one to the first.
+    Ring,
+    /// Every island sends to every other island.
+    FullyConnected,
+}
+
+impl Topology {
+    fn destinations(&self, island: usize, islands: usize) -> Vec<usize> {
+        match self {
+            Topology::Ring => vec![(island + 1) % islands],
+            Topology::FullyConnected => (0..islands).filter(|&other| other != island).collect(),
+        }
+    }
+}
+
+/// Parameters of `Problem::island_search`. The other GA parameters, including the total
+/// population shared out between the islands, come from `GaConfig`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct IslandConfig {
+    /// Number of sub-populations, each evolving on its own thread.
+    pub islands: usize,
+    /// Generations between two migrations.
+    pub migration_interval: u32,
+    /// Best individuals every island sends to each of its destinations.
+    pub migrants: usize,
+    /// Which islands migrants go to.
+    pub topology: Topology,
+}
+
+impl Default for IslandConfig {
+    fn default() -> Self {
+        IslandConfig {
+            islands: num_cpus::get().max(2),
+            migration_interval: 20,
+            migrants: 5,
+            topology: Topology::Ring,
+        }
+    }
+}
+
+impl Problem {
+    /// Island-model GA: `config.islands` sub-populations evolve on their own threads with their
+    /// own random generators, and every `config.migration_interval` generations the best
+    /// individuals of each island replace the worst ones of its destinations. When `seed` is
+    /// given the seeds of all islands are derived from it.
+    ///
+    /// The population of `GaConfig` is shared out as evenly as possible, but every island gets at
+    /// least one individual. Afterwards the population of the problem is the union of the
+    /// islands. The best fitness of every island after each epoch is the history of the result.
+    pub fn island_search(
+        &mut self,
+        selector: FnType,
+        config: &IslandConfig,
+        seed: Option<u64>,
+    ) -> SearchResult {
+        let now = Instant::now();
+        let islands = config.islands.max(1);
+        let mut random = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let population = self.config.population;
+        let mut islands: Vec<(Problem, StdRng)> = (0..islands)
+            .map(|island| {
+                let island_seed = seed.map(|seed| seed.wrapping_add(island as u64));
+                let mut problem = Problem::new(
+                    self.dimensions,
+                    self.pin_locations.clone(),
+                    island_seed.map(|seed| seed.wrapping_mul(population as u64)),
+                );
+                problem.obstacles = self.obstacles.clone();
+                problem.set_config(self.config.clone());
+                let share = population / islands + usize::from(island < population % islands);
+                problem.init_population(share.max(1));
+                (problem, StdRng::seed_from_u64(random.gen()))
+            })
+            .collect();
+
+        let bar = ProgressBar::new(self.config.iterations as u64);
+        let sty = ProgressStyle::default_bar()
+            .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
+            .progress_chars("=> ");
+        bar.set_style(sty);
+        bar.set_prefix("Iterating #");
+
+        let mut history = vec![];
+        let mut generation = 0;
+        let mut epoch = 0;
+        while generation < self.config.iterations {
+            let generations = config
+                .migration_interval
+                .max(1)
+                .min(self.config.iterations - generation);
+
+            thread::scope(|scope| {
+                for (problem, random) in islands.iter_mut() {
+                    scope.spawn(move || {
+                        for _ in 0..generations {
+                            problem.next_generation(selector, random);
+                        }
+                    });
+                }
+            });
+            generation += generations;
+            epoch += 1;
+            bar.inc(generations as u64);
+
+            if generation < self.config.iterations {
+                migrate(&mut islands, config);
+            }
+            history.push(EpochStats {
+                epoch,
+                generation,
+                island_bests: islands
+                    .iter()
+                    .map(|(problem, _)| problem.fittest().fitness())
+                    .collect(),
+            });
+        }
+
+        bar.finish_and_clear();
+
+        self.evaluations = islands.iter().map(|(problem, _)| problem.evaluations).sum();
+        self.generation = self.config.iterations;
+        self.population = islands
+            .into_iter()
+            .flat_map(|(problem, _)| problem.population)
+            .collect();
+        SearchResult::new(
+            self.best(1).remove(0).0,
+            now.elapsed(),
+            self.evaluations,
+            self.generation,
+            seed,
+            SearchConfig::Island {
+                ga: self.config.clone(),
+                islands: *config,
+            },
+        )
+        .with_history(SearchHistory::Epochs(history))
+    }
+
+    /// The `count` fittest individuals of the population, best first.
+    fn best(&self, count: usize) -> Vec<(Individual, f32)> {
+        let mut ranking: Vec<&(Individual, f32)> = self.population.iter().collect();
+        ranking.sort_by(|item1, item2| item1.1.partial_cmp(&item2.1).unwrap());
+        ranking.into_iter().take(count).cloned().collect()
+    }
+
+    /// Replaces the least fit individuals with `individuals`.
+    fn replace_worst(&mut self, individuals: Vec<(Individual, f32)>) {
+        self.population
+            .sort_by(|item1, item2| item1.1.partial_cmp(&item2.1).unwrap());
+        let size = self.population.len();
+        self.population
+            .truncate(size.saturating_sub(individuals.len()));
+        self.population.extend(individuals.into_iter().take(size));
+    }
+}
+
+/// Sends the best `config.migrants` of every island to its destinations. An island receiving
+/// more individuals than it holds, as can happen with `Topology::FullyConnected`, keeps the best
+/// of them.
+fn migrate(islands: &mut [(Problem, StdRng)], config: &IslandConfig) {
+    let emigrants: Vec<Vec<(Individual, f32)>> = islands
+        .iter()
+        .map(|(problem, _)| problem.best(config.migrants))
+        .collect();
+
+    let mut immigrants: Vec<Vec<(Individual, f32)>> = vec![vec![]; islands.len()];
+    for (island, emigrants) in emigrants.into_iter().enumerate() {
+        for destination in config.topology.destinations(island, islands.len()) {
+            immigrants[destination].extend(emigrants.iter().cloned());
+        }
+    }
+
+    for ((problem, _), mut immigrants) in islands.iter_mut().zip(immigrants) {
+        immigrants.sort_by(|item1, item2| item1.1.partial_cmp(&item2.1).unwrap());
+        immigrants.truncate(problem.population.len());
+        problem.replace_worst(immigrants);
+    }
+}
+
+#[cfg(test)]
+mod test;
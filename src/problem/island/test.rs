@@ -0,0 +1,133 @@
+use super::*;
+use crate::problem::fixtures::{assert_valid_individual, crossing_problem, CROSSING_PINS};
+use crate::problem::{tournament_selection, GaConfig};
+
+fn config(population: usize) -> GaConfig {
+    GaConfig {
+        population,
+        iterations: 4,
+        ..Default::default()
+    }
+}
+
+/// Islands of `size` individuals each, with different random populations.
+fn islands(count: usize, size: usize) -> Vec<(Problem, StdRng)> {
+    (0..count)
+        .map(|island| {
+            let mut problem = crossing_problem(island as u64 * 100, config(size));
+            problem.init_population(size);
+            (problem, StdRng::seed_from_u64(island as u64))
+        })
+        .collect()
+}
+
+fn holds(problem: &Problem, individual: &Individual) -> bool {
+    problem
+        .population
+        .iter()
+        .any(|(other, _)| other.distance(individual) == 0.)
+}
+
+#[test]
+fn test_island_search_seeded() {
+    let islands = IslandConfig {
+        islands: 3,
+        migration_interval: 2,
+        migrants: 2,
+        topology: Topology::Ring,
+    };
+    let mut first = crossing_problem(0, config(12));
+    let first = first.island_search(tournament_selection, &islands, Some(u64::MAX));
+    let mut second = crossing_problem(0, config(12));
+    let second = second.island_search(tournament_selection, &islands, Some(u64::MAX));
+
+    assert_eq!(first.fitness, second.fitness);
+    assert_eq!(first.individual.distance(&second.individual), 0.);
+    assert_eq!(first.generations, 4);
+    assert_valid_individual(&first.individual, (6, 6), &CROSSING_PINS);
+
+    assert_eq!(first.history, second.history);
+    match &first.history {
+        SearchHistory::Epochs(epochs) => {
+            let generations: Vec<u32> = epochs.iter().map(|stats| stats.generation).collect();
+            assert_eq!(generations, vec![2, 4]);
+            assert!(epochs.iter().all(|stats| stats.island_bests.len() == 3));
+        }
+        history => panic!("Expected epoch stats, got {:?}", history),
+    }
+}
+
+#[test]
+fn test_island_search_keeps_population_size() {
+    for (population, count, expected) in
+        IntoIterator::into_iter([(12, 3, 12), (10, 4, 10), (4, 8, 8)])
+    {
+        let mut problem = crossing_problem(1, config(population));
+        let islands = IslandConfig {
+            islands: count,
+            migration_interval: 1,
+            migrants: 3,
+            topology: Topology::FullyConnected,
+        };
+        problem.island_search(tournament_selection, &islands, Some(1));
+        assert_eq!(problem.population.len(), expected);
+    }
+}
+
+#[test]
+fn test_ring_migration_moves_best() {
+    let mut islands = islands(3, 5);
+    let bests: Vec<Individual> = islands
+        .iter()
+        .map(|(problem, _)| problem.best(1).remove(0).0)
+        .collect();
+    let config = IslandConfig {
+        islands: 3,
+        migration_interval: 1,
+        migrants: 1,
+        topology: Topology::Ring,
+    };
+    migrate(&mut islands, &config);
+
+    for (island, best) in bests.iter().enumerate() {
+        assert!(holds(&islands[(island + 1) % 3].0, best));
+    }
+    assert!(islands
+        .iter()
+        .all(|(problem, _)| problem.population.len() == 5));
+}
+
+#[test]
+fn test_fully_connected_migration_moves_best() {
+    for migrants in IntoIterator::into_iter([1, 10]) {
+        let mut islands = islands(4, 5);
+        let bests: Vec<Individual> = islands
+            .iter()
+            .map(|(problem, _)| problem.best(1).remove(0).0)
+            .collect();
+        let config = IslandConfig {
+            islands: 4,
+            migration_interval: 1,
+            migrants,
+            topology: Topology::FullyConnected,
+        };
+        migrate(&mut islands, &config);
+
+        for (island, (problem, _)) in islands.iter().enumerate() {
+            assert_eq!(problem.population.len(), 5);
+            let others = bests
+                .iter()
+                .enumerate()
+                .filter(|&(source, _)| source != island)
+                .map(|(_, best)| best);
+            if migrants == 1 {
+                assert!(others.clone().all(|best| holds(problem, best)));
+            }
+            // With more immigrants than room, at least the best of them all arrives.
+            let best = others
+                .min_by(|a, b| a.evaluate().partial_cmp(&b.evaluate()).unwrap())
+                .unwrap();
+            assert!(holds(problem, best), "{} misses the best immigrant", island);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Export of routed individuals as KiCad `.kicad_pcb` boards holding the nets, the board outline
+//! and one track segment per straight run of every route. Grid cell `(row, column)` is centred at
+//! `x = (column + 0.5) * pitch`, `y = (row + 0.5) * pitch` millimetres, so the outline runs along
+//! the outer edges of the border cells.
+
+use super::{Individual, Problem};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Parameters of `write_kicad`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct KicadConfig {
+    /// Distance between two grid cells in millimetres.
+    pub pitch: f32,
+    /// Trace width in millimetres.
+    pub width: f32,
+    /// Copper layer the tracks are placed on, `F.Cu`, `B.Cu` or one of `In1.Cu` to `In30.Cu`.
+    pub layer: String,
+}
+
+impl Default for KicadConfig {
+    fn default() -> Self {
+        KicadConfig {
+            pitch: 1.27,
+            width: 0.25,
+            layer: "F.Cu".to_string(),
+        }
+    }
+}
+
+/// Number KiCad gives the copper layer `name`: 0 for `F.Cu`, 1 to 30 for the inner layers
+/// `In1.Cu` to `In30.Cu` and 31 for `B.Cu`. `None` if `name` is no copper layer.
+pub fn copper_layer(name: &str) -> Option<u32> {
+    match name {
+        "F.Cu" => Some(0),
+        "B.Cu" => Some(31),
+        _ => name
+            .strip_prefix("In")
+            .and_then(|name| name.strip_suffix(".Cu"))
+            .filter(|number| {
+                !number.starts_with('0') && number.bytes().all(|byte| byte.is_ascii_digit())
+            })
+            .and_then(|number| number.parse().ok())
+            .filter(|number| (1..=30).contains(number)),
+    }
+}
+
+/// Writes the routes of `individual`, a solution of `problem`, as a KiCad board. Nets keep their
+/// names from the board file; unnamed ones are called `Net-<number>`. Fails with
+/// `InvalidInput` unless `config.layer` is a copper layer, see `copper_layer`.
+pub fn write_kicad(
+    problem: &Problem,
+    individual: &Individual,
+    config: &KicadConfig,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let layer = copper_layer(&config.layer).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a KiCad copper layer", config.layer),
+        )
+    })?;
+    let (rows, columns) = problem.dimensions();
+    let point = |(row, column): (u32, u32)| {
+        format!(
+            "{} {}",
+            mm((column as f32 + 0.5) * config.pitch),
+            mm((row as f32 + 0.5) * config.pitch)
+        )
+    };
+
+    writeln!(writer, "(kicad_pcb (version 20211014) (generator ga_pcb)")?;
+    writeln!(writer, "  (general (thickness 1.6))")?;
+    writeln!(writer, "  (paper \"A4\")")?;
+    writeln!(writer, "  (layers")?;
+    writeln!(writer, "    (0 \"F.Cu\" signal)")?;
+    if (1..31).contains(&layer) {
+        writeln!(writer, "    ({} {} signal)", layer, quoted(&config.layer))?;
+    }
+    writeln!(writer, "    (31 \"B.Cu\" signal)")?;
+    writeln!(writer, "    (44 \"Edge.Cuts\" user)")?;
+    writeln!(writer, "  )")?;
+
+    writeln!(writer, "  (net 0 \"\")")?;
+    for (index, name) in problem.net_names().iter().enumerate() {
+        let name = match name {
+            Some(name) => name.clone(),
+            None => format!("Net-{}", index + 1),
+        };
+        writeln!(writer, "  (net {} {})", index + 1, quoted(&name))?;
+    }
+
+    writeln!(
+        writer,
+        "  (gr_rect (start 0 0) (end {} {}) (layer \"Edge.Cuts\") (width 0.1))",
+        mm(columns as f32 * config.pitch),
+        mm(rows as f32 * config.pitch)
+    )?;
+
+    for (index, corners) in individual.polylines().iter().enumerate() {
+        for run in corners.windows(2).filter(|run| run[0] != run[1]) {
+            writeln!(
+                writer,
+                "  (segment (start {}) (end {}) (width {}) (layer {}) (net {}))",
+                point(run[0]),
+                point(run[1]),
+                mm(config.width),
+                quoted(&config.layer),
+                index + 1
+            )?;
+        }
+    }
+    writeln!(writer, ")")
+}
+
+/// Saves the routes of `individual` to the KiCad board at `path`, see `write_kicad`.
+pub fn save_kicad(
+    problem: &Problem,
+    individual: &Individual,
+    config: &KicadConfig,
+    path: &str,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_kicad(problem, individual, config, &mut writer)?;
+    writer.flush()
+}
+
+/// `text` as a KiCad string: in double quotes, with backslashes, quotes and line breaks escaped
+/// by a backslash.
+fn quoted(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Millimetres rounded to the micrometre KiCad works in, without trailing zeros.
+fn mm(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod test;
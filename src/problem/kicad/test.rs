@@ -0,0 +1,69 @@
+use super::*;
+use crate::problem::router::NetOrder;
+use crate::problem::ProblemBuilder;
+
+#[test]
+fn test_kicad_export() {
+    let problem = ProblemBuilder::new((6, 6))
+        .named_net("clock", (1, 1), (4, 3))
+        .net((0, 5), (5, 5))
+        .build()
+        .unwrap();
+    let individual = problem.astar_route(NetOrder::ShortestFirst);
+    let config = KicadConfig {
+        pitch: 2.5,
+        width: 0.3,
+        layer: "B.Cu".to_string(),
+    };
+    let mut output = vec![];
+    write_kicad(&problem, &individual, &config, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("(kicad_pcb "));
+    assert!(output.contains("(net 1 \"clock\")"));
+    assert!(output.contains("(net 2 \"Net-2\")"));
+    assert!(output.contains("(gr_rect (start 0 0) (end 15 15)"));
+    assert!(output.contains(
+        "(segment (start 13.75 1.25) (end 13.75 13.75) (width 0.3) (layer \"B.Cu\") (net 2))"
+    ));
+    let segments = output.matches("(segment ").count();
+    let runs: usize = individual
+        .polylines()
+        .iter()
+        .map(|corners| corners.windows(2).filter(|run| run[0] != run[1]).count())
+        .sum();
+    assert_eq!(segments, runs);
+    assert_eq!(output.matches('(').count(), output.matches(')').count());
+}
+
+#[test]
+fn test_kicad_layers_and_quoting() {
+    let problem = ProblemBuilder::new((4, 4))
+        .named_net("say \"hi\" \\ é", (0, 0), (3, 3))
+        .build()
+        .unwrap();
+    let individual = problem.lee_route();
+    let export = |layer: &str| {
+        let config = KicadConfig {
+            layer: layer.to_string(),
+            ..Default::default()
+        };
+        let mut output = vec![];
+        write_kicad(&problem, &individual, &config, &mut output).map(|_| output)
+    };
+
+    let output = String::from_utf8(export("In2.Cu").unwrap()).unwrap();
+    assert!(output.contains("(net 1 \"say \\\"hi\\\" \\\\ é\")"));
+    assert!(output.contains("    (2 \"In2.Cu\" signal)\n"));
+    assert!(output.contains("(layer \"In2.Cu\") (net 1))"));
+    let output = String::from_utf8(export("F.Cu").unwrap()).unwrap();
+    assert_eq!(output.matches(" signal)").count(), 2);
+
+    for layer in &["F.Silk", "In0.Cu", "In31.Cu", "In02.Cu", "In+2.Cu", "f.cu"] {
+        let error = export(layer).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+    assert_eq!(copper_layer("F.Cu"), Some(0));
+    assert_eq!(copper_layer("In30.Cu"), Some(30));
+    assert_eq!(copper_layer("B.Cu"), Some(31));
+}
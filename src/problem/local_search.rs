@@ -0,0 +1,105 @@
+//! Greedy hill climbing on a single individual, used by the memetic GA and the `climb`
+//! subcommand.
+
+use super::individual::Individual;
+use super::mutation::{Mutation, MutationKind, Targeting};
+use rand::rngs::StdRng;
+use serde::Serialize;
+
+/// Parameters of `hill_climb`.
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalSearchConfig {
+    /// Operators tried on every connection.
+    pub mutations: Vec<MutationKind>,
+    /// Tries per connection and operator in every pass.
+    pub attempts: u32,
+    /// Upper bound on the number of passes over the connections.
+    pub max_passes: u32,
+    /// Which segments the operators pick.
+    pub targeting: Targeting,
+}
+
+impl Default for LocalSearchConfig {
+    fn default() -> Self {
+        LocalSearchConfig {
+            mutations: vec![
+                MutationKind::Straighten,
+                MutationKind::ShiftSegment,
+                MutationKind::SplitSegment,
+                MutationKind::Reroute,
+            ],
+            attempts: 3,
+            max_passes: 20,
+            targeting: Targeting::Collisions,
+        }
+    }
+}
+
+/// Outcome of a `hill_climb` run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HillClimbStats {
+    /// Passes over the connections made.
+    pub passes: u32,
+    /// Changes kept.
+    pub improvements: u32,
+    /// Candidates scored.
+    pub evaluations: u64,
+    /// Fitness of the individual before the search.
+    pub start_fitness: f32,
+    /// Fitness of the individual after the search.
+    pub fitness: f32,
+}
+
+/// Greedy local search: tries every operator on every connection and keeps a change only if it
+/// lowers the fitness. Stops after a pass without any improvement or after `config.max_passes`.
+pub fn hill_climb(
+    individual: &mut Individual,
+    config: &LocalSearchConfig,
+    random: &mut StdRng,
+) -> HillClimbStats {
+    let start_fitness = individual.evaluate();
+    let mut fitness = start_fitness;
+    let mut passes = 0;
+    let mut improvements = 0;
+    let mut evaluations = 0;
+
+    while passes < config.max_passes {
+        passes += 1;
+        let mut improved = false;
+
+        for index in 0..individual.connection_count() {
+            for operator in &config.mutations {
+                for _ in 0..config.attempts {
+                    // Tried in place and undone when no better, which keeps the occupancy
+                    // counts tracked for `individual` valid.
+                    let previous = individual.connections()[index].clone();
+                    operator.mutate(individual, index, config.targeting, random);
+                    let candidate_fitness = individual.evaluate();
+                    evaluations += 1;
+                    if candidate_fitness < fitness {
+                        fitness = candidate_fitness;
+                        improvements += 1;
+                        improved = true;
+                    } else {
+                        individual.restore_connection(index, previous);
+                    }
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    HillClimbStats {
+        passes,
+        improvements,
+        evaluations,
+        start_fitness,
+        fitness,
+    }
+}
+
+#[cfg(test)]
+mod test;
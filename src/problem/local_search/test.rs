@@ -0,0 +1,45 @@
+use super::*;
+use crate::problem::fixtures::{assert_valid_individual, crossing_problem, CROSSING_PINS};
+use crate::problem::individual::generate_individual;
+use crate::problem::{tournament_selection, GaConfig};
+use rand::SeedableRng;
+
+#[test]
+fn test_hill_climb_never_worsens() {
+    let config = LocalSearchConfig::default();
+    for seed in 0..20 {
+        let mut individual = generate_individual((6, 6), CROSSING_PINS.to_vec(), Some(seed));
+        let mut random = StdRng::seed_from_u64(seed);
+        individual.mutate(&mut random, 1.);
+        let before = individual.evaluate();
+        let stats = hill_climb(&mut individual, &config, &mut random);
+        assert_eq!(stats.start_fitness, before);
+        assert_eq!(stats.fitness, individual.evaluate());
+        assert!(stats.fitness <= before);
+        let tries = config.mutations.len() as u64 * config.attempts as u64 * 2;
+        assert_eq!(stats.evaluations, stats.passes as u64 * tries);
+        assert_valid_individual(&individual, (6, 6), &CROSSING_PINS);
+    }
+}
+
+#[test]
+fn test_memetic_search_counts_local_search_evaluations() {
+    let config = GaConfig {
+        population: 10,
+        iterations: 3,
+        ..Default::default()
+    };
+    let mut plain = crossing_problem(8, config.clone());
+    let plain = plain.genetic_search(tournament_selection, Some(8));
+    let mut memetic = crossing_problem(
+        8,
+        GaConfig {
+            memetic_top_k: 2,
+            ..config
+        },
+    );
+    let memetic = memetic.genetic_search(tournament_selection, Some(8));
+
+    assert_eq!(plain.evaluations, 40);
+    assert!(memetic.evaluations >= 40 + 3 * 2 * 3 * 4 * 2);
+}
@@ -1,76 +1,235 @@
+//! Routing problems and the searches that solve them.
+
 extern crate num_cpus;
+pub mod adaptation;
+pub mod annealing;
+mod builder;
+mod config;
+pub mod crossover;
+pub mod diversity;
+#[cfg(test)]
+mod fixtures;
+pub mod generator;
 mod individual;
-
-use individual::*;
+pub mod io;
+pub mod island;
+pub mod kicad;
+pub mod local_search;
+pub mod mutation;
+pub mod nsga2;
+mod occupancy;
+pub mod result;
+pub mod router;
+pub mod specctra;
+pub mod steady_state;
+
+use adaptation::{OperatorStats, Rates};
+pub use builder::{BuildError, ProblemBuilder};
+pub use config::*;
+use crossover::Crossover;
+use diversity::Niching;
+pub use individual::*;
+pub use io::{load_problem, read_problem, save_problem, write_problem};
+use mutation::MutationKind;
+pub use result::{
+    parse_solution, read_solution, EpochStats, GenerationStats, SearchConfig, SearchHistory,
+    SearchResult,
+};
+use steady_state::Replacement;
 
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use rand::seq::SliceRandom;
-use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::sync::{Arc, Mutex};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-const CROSSOVER: f32 = 0.8;
-const MUTATION: f32 = 0.10;
-const ITERATIONS: u32 = 1000;
-const POPULATION: usize = 10000;
-const BATCH_SIZE: usize = 3;
-
+/// Routing problem: a grid of `dimensions` cells with pin pairs to connect, plus the population
+/// and state of the searches run on it.
 pub struct Problem {
     dimensions: (u32, u32),
     pin_locations: Vec<((u32, u32), (u32, u32))>,
+    net_names: Vec<Option<String>>,
+    obstacles: Arc<[(u32, u32)]>,
     population: Vec<(Individual, f32)>,
     random: Option<u64>,
+    config: GaConfig,
+    generation: u32,
+    rates: Rates,
+    operator_weights: Vec<f32>,
+    diversity: f32,
+    selection_fitness: Vec<f32>,
+    evaluations: u64,
 }
 
-type FnType = fn(problem: &mut Problem, batch_size: usize, random: &mut StdRng) -> Individual;
+const DIVERSITY_PAIRS: usize = 100;
 
+/// Parent selection used by the GA.
+pub type FnType = fn(problem: &mut Problem, batch_size: usize, random: &mut StdRng) -> Individual;
+
+/// Returns a copy of the fittest of `batch_size` randomly drawn individuals.
 pub fn tournament_selection(
     problem: &mut Problem,
     batch_size: usize,
     random: &mut StdRng,
 ) -> Individual {
-    let tournament_batch: Vec<(Individual, f32)> = problem
-        .population
-        .choose_multiple(random, batch_size)
-        .cloned()
-        .collect();
-    // tournament_batch
-    //     .choose_weighted_mut(random, |item| 1. / item.evaluate())
-    //     .unwrap()
-    //     .clone()
-    //
-    // tournament_batch.iter().min_by(|item1, item2| (item1.evaluate().partial_cmp(&item2.evaluate())).unwrap()).unwrap().clone()
-    tournament_batch
-        .iter()
-        .min_by(|item1, item2| item1.1.partial_cmp(&item2.1).unwrap())
-        .unwrap()
-        .0
-        .clone()
+    let batch = rand::seq::index::sample(
+        random,
+        problem.population.len(),
+        batch_size.min(problem.population.len()),
+    );
+    let winner = batch
+        .into_iter()
+        .min_by(|&a, &b| {
+            problem
+                .selection_fitness(a)
+                .partial_cmp(&problem.selection_fitness(b))
+                .unwrap()
+        })
+        .unwrap();
+    problem.population[winner].0.clone()
 }
 
-pub fn roulette_selection() {}
-
 impl Problem {
-    fn init_population(&mut self, size: usize) {
-        let bar = ProgressBar::new(size as u64);
+    /// Problem with the default `GaConfig`. `seed` makes the generated populations reproducible.
+    /// Doesn't validate the pins, so outside the crate problems are made with `ProblemBuilder`.
+    pub(crate) fn new(
+        dimensions: (u32, u32),
+        pin_locations: Vec<((u32, u32), (u32, u32))>,
+        seed: Option<u64>,
+    ) -> Problem {
+        Problem {
+            dimensions,
+            net_names: vec![None; pin_locations.len()],
+            pin_locations,
+            obstacles: Vec::new().into(),
+            population: vec![],
+            random: seed,
+            config: GaConfig::default(),
+            generation: 0,
+            rates: Rates {
+                mutation: 0.,
+                crossover: 0.,
+            },
+            operator_weights: vec![],
+            diversity: 0.,
+            selection_fitness: vec![],
+            evaluations: 0,
+        }
+    }
+
+    /// Parameters of the genetic searches.
+    pub fn config(&self) -> &GaConfig {
+        &self.config
+    }
+
+    /// Replaces the parameters of the genetic searches.
+    pub fn set_config(&mut self, config: GaConfig) {
+        self.config = config;
+    }
+
+    /// Rates used to breed the latest generation.
+    pub fn rates(&self) -> Rates {
+        self.rates
+    }
+
+    /// Current relative weights of `GaConfig::mutations`.
+    pub fn operator_weights(&self) -> &[f32] {
+        &self.operator_weights
+    }
+
+    /// Best individual, rates, operator weights and diversity of the latest generation, as
+    /// recorded by `genetic_search` after every generation.
+    pub fn generation_stats(&self) -> GenerationStats {
+        GenerationStats {
+            generation: self.generation,
+            best: self.fittest().fitness(),
+            rates: self.rates,
+            operator_weights: self.operator_weights.clone(),
+            diversity: self.diversity,
+        }
+    }
+
+    /// Fittest individual of the current population.
+    fn fittest(&self) -> &Individual {
+        &self
+            .population
+            .iter()
+            .min_by(|item1, item2| (item1.1.partial_cmp(&item2.1)).unwrap())
+            .expect("The population is empty")
+            .0
+    }
+
+    /// Mean structural distance between individuals of the current population, see
+    /// `diversity::population_diversity`.
+    pub fn diversity(&self) -> f32 {
+        self.diversity
+    }
+
+    /// Individuals generated or bred, and memetic local search candidates scored, since the
+    /// population was initialised.
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+
+    /// Fitness the selector compares, raised for individuals in crowded niches under
+    /// `Niching::Sharing`.
+    fn selection_fitness(&self, index: usize) -> f32 {
+        match self.selection_fitness.get(index) {
+            Some(points) => *points,
+            None => self.population[index].1,
+        }
+    }
+
+    /// Rows and columns of the board.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    /// Pins of every net.
+    pub fn pin_locations(&self) -> &[PinPair] {
+        &self.pin_locations
+    }
+
+    /// Name of every net, in the order of `pin_locations`, if it was given one.
+    pub fn net_names(&self) -> &[Option<String>] {
+        &self.net_names
+    }
+
+    /// Cells no trace may use.
+    pub fn obstacles(&self) -> &[(u32, u32)] {
+        &self.obstacles
+    }
+
+    /// Fills the population up to `size` with random individuals, keeping any seeded ones.
+    pub fn init_population(&mut self, size: usize) {
+        self.populate(size, self.random);
+    }
+
+    /// `init_population` with individual `i` generated from `seed + i`, or at random without a
+    /// seed.
+    fn populate(&mut self, size: usize, seed: Option<u64>) {
+        self.generation = 0;
+        self.evaluations = size.max(self.population.len()) as u64;
+        self.operator_weights = self
+            .config
+            .mutations
+            .iter()
+            .map(|operator| operator.1)
+            .collect();
+
+        let bar = ProgressBar::new(size.saturating_sub(self.population.len()) as u64);
         let sty = ProgressStyle::default_bar()
             .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
             .progress_chars("=> ");
         bar.set_style(sty);
         bar.set_prefix("Generating population #");
 
-        for i in 0..size {
-            let individual: Individual = generate_individual(
-                self.dimensions.clone(),
+        for i in self.population.len()..size {
+            let individual: Individual = generate_individual_with_obstacles(
+                self.dimensions,
                 self.pin_locations.clone(),
-                match self.random {
-                    Some(seed) => Some(seed + i as u64),
-                    None => None,
-                },
+                self.obstacles.clone(),
+                seed.map(|seed| seed.wrapping_add(i as u64)),
             );
             let points = individual.evaluate();
             self.population.push((individual, points));
@@ -79,187 +238,349 @@ impl Problem {
         bar.finish_and_clear();
     }
 
-    pub fn genetic_search(
-        &mut self,
-        selector: FnType,
-        cpus: Option<usize>,
+    /// Adds ready-made individuals, e.g. from one of the routers, to the population the next
+    /// `genetic_search` starts from.
+    pub fn seed_population(&mut self, individuals: Vec<Individual>) {
+        for individual in individuals {
+            let individual = individual.with_obstacles(self.obstacles.clone());
+            let points = individual.evaluate();
+            self.population.push((individual, points));
+        }
+    }
+
+    /// Deterministic baseline: routes the nets in order with `router::lee_route`.
+    pub fn lee_route(&self) -> Individual {
+        router::lee_route(self.dimensions, &self.pin_locations, &self.obstacles)
+    }
+
+    /// Routes the nets in `order` with `router::astar_route`.
+    pub fn astar_route(&self, order: router::NetOrder) -> Individual {
+        router::astar_route(self.dimensions, &self.pin_locations, &self.obstacles, order)
+    }
+
+    /// Routes the nets with the negotiated-congestion `router::pathfinder_route`.
+    pub fn pathfinder_route(&self, config: &router::PathFinderConfig) -> router::PathFinderResult {
+        router::pathfinder_route(
+            self.dimensions,
+            &self.pin_locations,
+            &self.obstacles,
+            config,
+        )
+    }
+
+    /// Single-solution local search, see `annealing::simulated_annealing`. The steps recorded by
+    /// every chain are the history of the result.
+    pub fn simulated_annealing(
+        &self,
+        config: &annealing::AnnealingConfig,
         seed: Option<u64>,
-    ) -> (Individual, f32) {
+    ) -> SearchResult {
+        annealing::simulated_annealing(
+            self.dimensions,
+            &self.pin_locations,
+            &self.obstacles,
+            config,
+            seed,
+        )
+    }
+
+    /// Runs `GaConfig::iterations` generations from a fresh population and returns the best
+    /// individual found, with the `generation_stats` of every generation as its history. The
+    /// initial population is drawn from `seed` too, so a seeded run is reproduced by its seed
+    /// alone, whatever the seed of the problem.
+    ///
+    /// Panics if `GaConfig::population` or `GaConfig::batch_size` is 0.
+    pub fn genetic_search(&mut self, selector: FnType, seed: Option<u64>) -> SearchResult {
+        assert!(
+            self.config.population > 0,
+            "genetic_search needs a population of at least one individual"
+        );
+        assert!(
+            self.config.batch_size > 0,
+            "genetic_search needs a selection batch of at least one individual"
+        );
+        let now = Instant::now();
         let mut random = match seed {
             Some(seed) => StdRng::seed_from_u64(seed),
             None => StdRng::from_entropy(),
         };
 
-        self.init_population(POPULATION);
+        self.populate(self.config.population, seed);
 
-        let bar = ProgressBar::new(ITERATIONS as u64);
+        let bar = ProgressBar::new(self.config.iterations as u64);
         let sty = ProgressStyle::default_bar()
             .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
             .progress_chars("=> ");
         bar.set_style(sty);
         bar.set_prefix("Iterating #");
 
-        for _ in 0..ITERATIONS {
-            let mut new_population: Vec<(Individual, f32)> = vec![];
-            while new_population.len() < POPULATION {
-                let mut i1 = selector(self, BATCH_SIZE, &mut random);
-                if random.gen::<f32>() < CROSSOVER {
-                    let mut i2 = selector(self, BATCH_SIZE, &mut random);
-                    i1.crossover(&mut i2, random.gen());
-                }
-                i1.mutate(&mut random, MUTATION);
-                let points = i1.evaluate();
-                new_population.push((i1, points));
-            }
-            self.population = new_population;
+        let mut history = vec![];
+        for _ in 0..self.config.iterations {
+            self.next_generation(selector, &mut random);
+            history.push(self.generation_stats());
             bar.inc(1);
-            println!(
-                "{}",
-                self.population
-                    .iter()
-                    .min_by(|item1, item2| (item1.1.partial_cmp(&item2.1)).unwrap())
-                    .unwrap()
-                    .0
-            );
-            println!(
-                "{}",
-                self.population
-                    .iter()
-                    .min_by(|item1, item2| (item1.1.partial_cmp(&item2.1)).unwrap())
-                    .unwrap()
-                    .1
-            );
         }
 
         bar.finish_and_clear();
 
-        self.population
-            .iter()
-            .min_by(|item1, item2| (item1.1.partial_cmp(&item2.1)).unwrap())
-            .unwrap()
-            .clone()
+        SearchResult::new(
+            self.fittest().clone(),
+            now.elapsed(),
+            self.evaluations,
+            self.generation,
+            seed,
+            SearchConfig::Genetic(self.config.clone()),
+        )
+        .with_history(SearchHistory::Generations(history))
     }
 
-    pub fn random_search(&mut self, iterations: u64, cpus: Option<usize>) -> (Individual, u128) {
-        let now = Instant::now();
-
-        let cpus = cpus.unwrap_or(num_cpus::get() / 2);
-        // let mut stats = vec![vec![0; problem.dimensions.1 as usize]; problem.dimensions.0 as usize];
-        let safe_best_individuals: Arc<Mutex<Vec<Individual>>> = Arc::new(Mutex::new(vec![]));
-
-        let m = MultiProgress::new();
-        let sty = ProgressStyle::default_bar()
-            .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
-            .progress_chars("=> ");
-
-        if self.random.is_some() {
-            let pb = m.add(ProgressBar::new(iterations / cpus as u64));
-            let mut best: Individual = Individual::new();
-            let mut best_value = std::f32::INFINITY;
-            for i in 0..iterations {
-                let temp = generate_individual(
-                    self.dimensions.clone(),
-                    self.pin_locations.clone(),
-                    Some(self.random.unwrap() + i as u64),
-                );
-                let temp_value = temp.evaluate();
-                if temp_value < best_value {
-                    best_value = temp_value;
-                    best = temp;
-                };
-                pb.inc(1);
+    /// Breeds one generation according to the configuration.
+    pub fn next_generation(&mut self, selector: FnType, random: &mut StdRng) {
+        if self.generation == 0 {
+            self.measure_diversity(random);
+        }
+        self.rates = self
+            .config
+            .rate_schedule
+            .rates(&self.config, self.generation, self.diversity);
+        if self.operator_weights.len() != self.config.mutations.len() {
+            self.operator_weights = self
+                .config
+                .mutations
+                .iter()
+                .map(|operator| operator.1)
+                .collect();
+        }
+        let operators: Vec<_> = self
+            .config
+            .mutations
+            .iter()
+            .zip(&self.operator_weights)
+            .map(|(operator, weight)| (operator.0, *weight))
+            .collect();
+        let mut stats = OperatorStats::new(operators.len());
+
+        self.selection_fitness = match self.config.niching {
+            Niching::Sharing { radius, alpha } => {
+                diversity::shared_fitness(&self.population, radius, alpha, random)
             }
-            safe_best_individuals.lock().unwrap().push(best);
-        } else {
-            let handles = (0..cpus)
-                .into_iter()
-                .map(|x| {
-                    let dimensions = self.dimensions;
-                    let pin_locations = self.pin_locations.clone();
-                    let best_individuals = Arc::clone(&safe_best_individuals);
-                    let pb = m.add(ProgressBar::new(iterations / cpus as u64));
-                    pb.set_prefix(&format!("Thread #{}", x));
-                    pb.set_style(sty.clone());
-                    thread::spawn(move || {
-                        let mut best: Individual = Individual::new();
-                        let mut best_value = std::f32::INFINITY;
-                        for _ in 0..iterations / cpus as u64 {
-                            let temp = generate_individual(
-                                dimensions.clone(),
-                                pin_locations.clone(),
-                                None,
-                            );
-                            let temp_value = temp.evaluate();
-                            if temp_value < best_value {
-                                best_value = temp_value;
-                                best = temp;
-                            };
-                            pb.inc(1);
-                        }
-                        best_individuals.lock().unwrap().push(best);
-                    })
-                })
-                .collect::<Vec<thread::JoinHandle<_>>>();
+            _ => vec![],
+        };
 
-            m.join_and_clear().unwrap();
+        if let Replacement::SteadyState { offspring, policy } = self.config.replacement {
+            self.steady_state(selector, offspring, policy, &operators, &mut stats, random);
+        } else {
+            self.generational(selector, &operators, &mut stats, random);
+        }
+        self.selection_fitness.clear();
+
+        if self.config.eliminate_duplicates {
+            diversity::eliminate_duplicates(
+                &mut self.population,
+                self.dimensions,
+                &self.pin_locations,
+                &self.obstacles,
+                random,
+            );
+        }
 
-            for thread in handles {
-                thread.join().unwrap();
+        if self.config.memetic_top_k > 0 {
+            let mut ranking: Vec<usize> = (0..self.population.len()).collect();
+            ranking.sort_by(|&a, &b| {
+                self.population[a]
+                    .1
+                    .partial_cmp(&self.population[b].1)
+                    .unwrap()
+            });
+            for &index in ranking.iter().take(self.config.memetic_top_k) {
+                let (individual, points) = &mut self.population[index];
+                let stats = local_search::hill_climb(individual, &self.config.local_search, random);
+                *points = stats.fitness;
+                self.evaluations += stats.evaluations;
             }
         }
 
-        let safe_best_individuals = safe_best_individuals.lock().unwrap();
-        let mut best = &safe_best_individuals[0];
+        if self.config.adapt_operators {
+            stats.adapt(&mut self.operator_weights);
+        }
+        self.measure_diversity(random);
+        self.generation += 1;
+    }
 
-        for i in 1..safe_best_individuals.len() {
-            if safe_best_individuals[i].evaluate() > best.evaluate() {
-                best = &safe_best_individuals[i];
-            }
+    /// Replaces the whole population with as many offspring, or with the winners of the
+    /// parent-child competitions under `Niching::Crowding`.
+    fn generational(
+        &mut self,
+        selector: FnType,
+        operators: &[(MutationKind, f32)],
+        stats: &mut OperatorStats,
+        random: &mut StdRng,
+    ) {
+        if self.config.niching == Niching::Crowding {
+            self.crowding(operators, stats, random);
+            return;
         }
 
-        (best.clone(), now.elapsed().as_millis())
+        let size = self.population.len();
+        let mut new_population: Vec<(Individual, f32)> = vec![];
+        while new_population.len() < size {
+            let parent = selector(self, self.config.batch_size, random);
+            let other = selector(self, self.config.batch_size, random);
+            let crossover = random.gen::<f32>() < self.rates.crossover;
+            let child = self.offspring(&parent, &other, crossover, operators, stats, random);
+            self.evaluations += 1;
+            let points = child.evaluate();
+            new_population.push((child, points));
+        }
+        self.population = new_population;
     }
-}
 
-pub fn load_problem(problem_name: &str, seed: Option<u64>) -> Problem {
-    fn parse_pairs(mut pairs: Vec<&str>) -> Vec<(u32, u32)> {
-        let mut parsed_pairs: Vec<(u32, u32)> = Vec::new();
+    /// Deterministic crowding: pairs the population up at random, the last individual of an odd
+    /// population with a random partner, and lets the two children of every pair compete against
+    /// the parent closest to them.
+    fn crowding(
+        &mut self,
+        operators: &[(MutationKind, f32)],
+        stats: &mut OperatorStats,
+        random: &mut StdRng,
+    ) {
+        let size = self.population.len();
+        let mut order: Vec<usize> = (0..size).collect();
+        order.shuffle(random);
+        if size % 2 == 1 {
+            order.push(random.gen_range(0..size));
+        }
 
-        while pairs.len() > 1 {
-            let strings: (&str, &str) = (pairs.pop().unwrap(), pairs.pop().unwrap());
-            let pair: (u32, u32) = (
-                strings.1.trim().parse().unwrap(),
-                strings.0.trim().parse().unwrap(),
-            );
-            parsed_pairs.push(pair);
+        let mut new_population: Vec<(Individual, f32)> = vec![];
+        for pair in order.chunks(2) {
+            let (parent, other) = (&self.population[pair[0]], &self.population[pair[1]]);
+            let crossover = random.gen::<f32>() < self.rates.crossover;
+            let child = self.offspring(&parent.0, &other.0, crossover, operators, stats, random);
+            let second = self.offspring(&other.0, &parent.0, crossover, operators, stats, random);
+            let pairs = if parent.0.distance(&child) + other.0.distance(&second)
+                <= parent.0.distance(&second) + other.0.distance(&child)
+            {
+                [(parent, child), (other, second)]
+            } else {
+                [(parent, second), (other, child)]
+            };
+            for (parent, child) in IntoIterator::into_iter(pairs) {
+                let child_points = child.evaluate();
+                if child_points <= parent.1 {
+                    new_population.push((child, child_points));
+                } else {
+                    new_population.push(parent.clone());
+                }
+            }
         }
+        self.evaluations += 2 * (order.len() / 2) as u64;
+        new_population.truncate(size);
+        self.population = new_population;
+    }
 
-        return parsed_pairs;
+    /// Child of `parent`, crossed with `other` when `crossover` is set, then mutated.
+    fn offspring(
+        &self,
+        parent: &Individual,
+        other: &Individual,
+        crossover: bool,
+        operators: &[(MutationKind, f32)],
+        stats: &mut OperatorStats,
+        random: &mut StdRng,
+    ) -> Individual {
+        let mut child = parent.clone();
+        if crossover {
+            self.config.crossover.crossover(&mut child, other, random);
+        }
+        mutation::mutate(
+            &mut child,
+            operators,
+            self.rates.mutation,
+            self.config.targeting,
+            random,
+            Some(stats),
+        );
+        child
     }
 
-    let file = File::open(problem_name).expect("Failed to open file");
-    let reader = BufReader::new(file);
-    let mut dimensions = (0, 0);
-    let mut pin_locations: Vec<((u32, u32), (u32, u32))> = Vec::new();
+    fn measure_diversity(&mut self, random: &mut StdRng) {
+        self.diversity = diversity::population_diversity(&self.population, DIVERSITY_PAIRS, random);
+    }
 
-    for (index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let unparsed_dimensions: Vec<_> = line.trim().split(';').collect();
+    /// Samples `iterations` random individuals split over `cpus` threads, half the cores by
+    /// default, and returns the fittest one with its fitness. Every thread draws from its own
+    /// generator, seeded with the problem seed plus the thread number when the problem is seeded,
+    /// so seeded runs with the same number of threads are reproducible.
+    ///
+    /// Panics if `iterations` is 0.
+    pub fn random_search(&mut self, iterations: u64, cpus: Option<usize>) -> SearchResult {
+        assert!(iterations > 0, "random_search needs at least one iteration");
+        let now = Instant::now();
+        let cpus = cpus.unwrap_or(num_cpus::get() / 2).max(1);
 
-        if index == 0 && unparsed_dimensions.len() == 2 {
-            dimensions = parse_pairs(unparsed_dimensions)[0];
-        } else if unparsed_dimensions.len() == 4 {
-            let parsed_pairs = parse_pairs(unparsed_dimensions);
-            pin_locations.push((parsed_pairs[1], parsed_pairs[0]));
-        } else {
-            panic!("Wrong test data format");
-        }
-    }
+        let m = MultiProgress::new();
+        let sty = ProgressStyle::default_bar()
+            .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
+            .progress_chars("=> ");
 
-    Problem {
-        dimensions,
-        pin_locations,
-        population: vec![],
-        random: seed,
+        let handles = (0..cpus)
+            .map(|x| {
+                let share =
+                    iterations / cpus as u64 + u64::from((x as u64) < iterations % cpus as u64);
+                let dimensions = self.dimensions;
+                let pin_locations = self.pin_locations.clone();
+                let obstacles = self.obstacles.clone();
+                let mut random = match self.random {
+                    Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(x as u64)),
+                    None => StdRng::from_entropy(),
+                };
+                let pb = m.add(ProgressBar::new(share));
+                pb.set_prefix(&format!("Thread #{}", x));
+                pb.set_style(sty.clone());
+                thread::spawn(move || {
+                    let mut best: Option<(Individual, f32)> = None;
+                    for _ in 0..share {
+                        let temp = generate_individual_with_obstacles(
+                            dimensions,
+                            pin_locations.clone(),
+                            obstacles.clone(),
+                            Some(random.gen()),
+                        );
+                        let temp_value = temp.evaluate();
+                        if best.as_ref().is_none_or(|best| temp_value < best.1) {
+                            best = Some((temp, temp_value));
+                        }
+                        pb.inc(1);
+                    }
+                    pb.finish();
+                    best
+                })
+            })
+            .collect::<Vec<thread::JoinHandle<_>>>();
+
+        m.join_and_clear().unwrap();
+
+        let best = handles
+            .into_iter()
+            .filter_map(|thread| thread.join().unwrap())
+            .fold(None, |best: Option<(Individual, f32)>, item| match best {
+                Some(best) if best.1 <= item.1 => Some(best),
+                _ => Some(item),
+            })
+            .unwrap();
+        SearchResult::new(
+            best.0,
+            now.elapsed(),
+            iterations,
+            0,
+            self.random,
+            SearchConfig::Random {
+                iterations,
+                threads: cpus,
+            },
+        )
     }
 }
+
+#[cfg(test)]
+mod test;
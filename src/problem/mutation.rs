@@ -0,0 +1,189 @@
+//! Mutation operators changing the route of one connection, and `mutate`, which applies them to
+//! a whole individual, optionally directed at the collisions.
+
+use super::adaptation::OperatorStats;
+use super::individual::Individual;
+use rand::distributions::WeightedIndex;
+use rand::{rngs::StdRng, Rng};
+use serde::Serialize;
+
+const CONNECTION_TARGET_BASE: f32 = 0.1;
+
+/// Changes the route of a single connection of an individual.
+pub trait Mutation {
+    /// Changes the connection at `index`, picking its segments as `targeting` says.
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        targeting: Targeting,
+        random: &mut StdRng,
+    );
+}
+
+/// Pushes a whole segment sideways.
+pub struct ShiftSegment;
+
+/// Pushes the tail of a segment sideways, turning it into a detour.
+pub struct SplitSegment;
+
+/// Removes a U-turn, or pulls a corner in when there is none.
+pub struct Straighten;
+
+/// Replaces a random sub-path with a fresh random walk.
+pub struct Reroute;
+
+/// Routes the whole net again.
+pub struct Regenerate;
+
+impl Mutation for ShiftSegment {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        individual.shift_segment(index, targeting, random);
+    }
+}
+
+impl Mutation for SplitSegment {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        individual.split_segment(index, targeting, random);
+    }
+}
+
+impl Mutation for Straighten {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        _targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        individual.straighten(index, random);
+    }
+}
+
+impl Mutation for Reroute {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        _targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        individual.reroute(index, random);
+    }
+}
+
+impl Mutation for Regenerate {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        _targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        individual.regenerate(index, random);
+    }
+}
+
+/// Where mutations are directed.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Targeting {
+    /// Every connection and segment is equally likely to be mutated.
+    Uniform,
+    /// Connections and segments running through collision cells are mutated more often.
+    Collisions,
+}
+
+/// Mutation operator selectable in `GaConfig`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum MutationKind {
+    /// See `ShiftSegment`.
+    ShiftSegment,
+    /// See `SplitSegment`.
+    SplitSegment,
+    /// See `Straighten`.
+    Straighten,
+    /// See `Reroute`.
+    Reroute,
+    /// See `Regenerate`.
+    Regenerate,
+}
+
+impl Mutation for MutationKind {
+    fn mutate(
+        &self,
+        individual: &mut Individual,
+        index: usize,
+        targeting: Targeting,
+        random: &mut StdRng,
+    ) {
+        match self {
+            MutationKind::ShiftSegment => ShiftSegment.mutate(individual, index, targeting, random),
+            MutationKind::SplitSegment => SplitSegment.mutate(individual, index, targeting, random),
+            MutationKind::Straighten => Straighten.mutate(individual, index, targeting, random),
+            MutationKind::Reroute => Reroute.mutate(individual, index, targeting, random),
+            MutationKind::Regenerate => Regenerate.mutate(individual, index, targeting, random),
+        }
+    }
+}
+
+/// Mutates every connection with probability `rate`, using an operator drawn from `operators`
+/// in proportion to its weight. With `Targeting::Collisions` the per-connection probability is
+/// redistributed towards connections involved in collisions, keeping the expected number of
+/// mutations the same. When `stats` is given, every application is recorded together with
+/// whether it improved the individual.
+pub fn mutate<M: Mutation>(
+    individual: &mut Individual,
+    operators: &[(M, f32)],
+    rate: f32,
+    targeting: Targeting,
+    random: &mut StdRng,
+    mut stats: Option<&mut OperatorStats>,
+) {
+    let choice = match WeightedIndex::new(operators.iter().map(|operator| operator.1)) {
+        Ok(choice) => choice,
+        Err(_) => return,
+    };
+
+    let chances = match targeting {
+        Targeting::Uniform => vec![rate; individual.connection_count()],
+        Targeting::Collisions => targeted_chances(individual, rate),
+    };
+
+    for (index, chance) in chances.into_iter().enumerate() {
+        if random.gen::<f32>() < chance {
+            let operator = random.sample(&choice);
+            let before = individual.evaluate();
+            operators[operator]
+                .0
+                .mutate(individual, index, targeting, random);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.record(operator, individual.evaluate() < before);
+            }
+        }
+    }
+}
+
+fn targeted_chances(individual: &Individual, rate: f32) -> Vec<f32> {
+    let weights: Vec<f32> = individual
+        .connection_collisions()
+        .into_iter()
+        .map(|collisions| collisions as f32 + CONNECTION_TARGET_BASE)
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights
+        .iter()
+        .map(|weight| (rate * weights.len() as f32 * weight / total).min(1.))
+        .collect()
+}
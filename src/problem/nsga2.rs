@@ -0,0 +1,258 @@
+//! NSGA-II: multi-objective search trading collisions, length and segments off against each
+//! other instead of adding them up into one fitness.
+
+use super::crossover::Crossover;
+use super::individual::Individual;
+use super::{mutation, Problem};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Objectives minimised by `Problem::nsga2_search`: collisions, total trace length and number of
+/// segments. Boards are single-layer, so there are no vias to count.
+pub type Objectives = [u32; 3];
+
+/// The `Objectives` of `individual`.
+pub fn objectives(individual: &Individual) -> Objectives {
+    [
+        individual.collisions(),
+        individual.total_length(),
+        individual.segment_count() as u32,
+    ]
+}
+
+fn dominates(a: &Objectives, b: &Objectives) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b) && a != b
+}
+
+/// Splits `objectives` into fronts of mutually non-dominated indices, best front first.
+pub fn non_dominated_sort(objectives: &[Objectives]) -> Vec<Vec<usize>> {
+    let mut dominated_by: Vec<Vec<usize>> = vec![vec![]; objectives.len()];
+    let mut domination_count = vec![0; objectives.len()];
+    let mut fronts = vec![vec![]];
+
+    for a in 0..objectives.len() {
+        for b in (a + 1)..objectives.len() {
+            if dominates(&objectives[a], &objectives[b]) {
+                dominated_by[a].push(b);
+                domination_count[b] += 1;
+            } else if dominates(&objectives[b], &objectives[a]) {
+                dominated_by[b].push(a);
+                domination_count[a] += 1;
+            }
+        }
+    }
+    for (index, count) in domination_count.iter().enumerate() {
+        if *count == 0 {
+            fronts[0].push(index);
+        }
+    }
+
+    while !fronts.last().unwrap().is_empty() {
+        let mut next = vec![];
+        for &a in fronts.last().unwrap() {
+            for &b in &dominated_by[a] {
+                domination_count[b] -= 1;
+                if domination_count[b] == 0 {
+                    next.push(b);
+                }
+            }
+        }
+        fronts.push(next);
+    }
+    fronts.pop();
+    fronts
+}
+
+/// Crowding distance of every member of `front`, in the same order. Boundary members get an
+/// infinite distance so they are always kept.
+pub fn crowding_distance(front: &[usize], objectives: &[Objectives]) -> Vec<f32> {
+    let mut distance = vec![0.; front.len()];
+    if front.is_empty() {
+        return distance;
+    }
+    let members: Vec<Objectives> = front.iter().map(|&index| objectives[index]).collect();
+    for objective in 0..3 {
+        let value: Vec<f32> = members
+            .iter()
+            .map(|member| member[objective] as f32)
+            .collect();
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| value[a].partial_cmp(&value[b]).unwrap());
+
+        let range = value[order[front.len() - 1]] - value[order[0]];
+        distance[order[0]] = f32::INFINITY;
+        distance[order[front.len() - 1]] = f32::INFINITY;
+        if range == 0. {
+            continue;
+        }
+        for window in order.windows(3) {
+            distance[window[1]] += (value[window[2]] - value[window[0]]) / range;
+        }
+    }
+    distance
+}
+
+impl Problem {
+    /// NSGA-II: evolves a population of `GaConfig::population` individuals for
+    /// `GaConfig::iterations` generations using the configured crossover and mutation operators,
+    /// selecting by Pareto rank and crowding distance over `Objectives` instead of the weighted
+    /// fitness. Returns the final Pareto front, one individual per distinct objective vector,
+    /// sorted by collisions.
+    pub fn nsga2_search(&mut self, seed: Option<u64>) -> Vec<(Individual, Objectives)> {
+        let mut random = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        self.init_population(self.config.population);
+        let size = self.population.len();
+        let mut population: Vec<(Individual, Objectives)> = self
+            .population
+            .drain(..)
+            .map(|(individual, _)| {
+                let objectives = objectives(&individual);
+                (individual, objectives)
+            })
+            .collect();
+        let (mut ranks, mut distances) = rank(&population);
+
+        let bar = ProgressBar::new(self.config.iterations as u64);
+        let sty = ProgressStyle::default_bar()
+            .template("{prefix:.cyan}   [{bar:40.white}] {pos:>7}/{len:7} [{elapsed_precise}]")
+            .progress_chars("=> ");
+        bar.set_style(sty);
+        bar.set_prefix("Iterating #");
+
+        for _ in 0..self.config.iterations {
+            let mut offspring = vec![];
+            while offspring.len() < size {
+                let mut child = population[crowded_tournament(&ranks, &distances, &mut random)]
+                    .0
+                    .clone();
+                if random.gen::<f32>() < self.config.crossover_rate {
+                    let other = &population[crowded_tournament(&ranks, &distances, &mut random)].0;
+                    self.config
+                        .crossover
+                        .crossover(&mut child, other, &mut random);
+                }
+                mutation::mutate(
+                    &mut child,
+                    &self.config.mutations,
+                    self.config.mutation_rate,
+                    self.config.targeting,
+                    &mut random,
+                    None,
+                );
+                let objectives = objectives(&child);
+                offspring.push((child, objectives));
+            }
+            population.extend(offspring);
+
+            let all: Vec<Objectives> = population.iter().map(|item| item.1).collect();
+            let mut survivors = vec![];
+            for front in non_dominated_sort(&all) {
+                if survivors.len() + front.len() <= size {
+                    survivors.extend(front);
+                    continue;
+                }
+                let distance = crowding_distance(&front, &all);
+                let mut order: Vec<usize> = (0..front.len()).collect();
+                order.sort_by(|&a, &b| distance[b].partial_cmp(&distance[a]).unwrap());
+                survivors.extend(
+                    order
+                        .into_iter()
+                        .take(size - survivors.len())
+                        .map(|i| front[i]),
+                );
+                break;
+            }
+
+            let mut population_slots: Vec<Option<(Individual, Objectives)>> =
+                population.into_iter().map(Some).collect();
+            population = survivors
+                .into_iter()
+                .map(|index| population_slots[index].take().unwrap())
+                .collect();
+            let ranking = rank(&population);
+            ranks = ranking.0;
+            distances = ranking.1;
+            bar.inc(1);
+        }
+
+        bar.finish_and_clear();
+
+        let mut front: Vec<(Individual, Objectives)> = population
+            .into_iter()
+            .zip(ranks)
+            .filter(|(_, rank)| *rank == 0)
+            .map(|(item, _)| item)
+            .collect();
+        front.sort_by_key(|item| item.1);
+        front.dedup_by_key(|item| item.1);
+        front
+    }
+}
+
+/// Pareto rank and crowding distance of every member of `population`.
+fn rank(population: &[(Individual, Objectives)]) -> (Vec<usize>, Vec<f32>) {
+    let objectives: Vec<Objectives> = population.iter().map(|item| item.1).collect();
+    let mut ranks = vec![0; population.len()];
+    let mut distances = vec![0.; population.len()];
+    for (rank, front) in non_dominated_sort(&objectives).into_iter().enumerate() {
+        for (index, distance) in front.iter().zip(crowding_distance(&front, &objectives)) {
+            ranks[*index] = rank;
+            distances[*index] = distance;
+        }
+    }
+    (ranks, distances)
+}
+
+/// Binary tournament preferring the lower rank, then the larger crowding distance.
+fn crowded_tournament(ranks: &[usize], distances: &[f32], random: &mut StdRng) -> usize {
+    let a = random.gen_range(0..ranks.len());
+    let b = random.gen_range(0..ranks.len());
+    match ranks[a].cmp(&ranks[b]) {
+        Ordering::Less => a,
+        Ordering::Greater => b,
+        Ordering::Equal if distances[a] >= distances[b] => a,
+        Ordering::Equal => b,
+    }
+}
+
+/// Writes a Pareto front for inspection: a `collisions;length;segments` table followed by the
+/// corner points of every net of every individual.
+pub fn export_front(front: &[(Individual, Objectives)], path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    writeln!(file, "collisions;length;segments")?;
+    for (_, objectives) in front {
+        writeln!(
+            file,
+            "{};{};{}",
+            objectives[0], objectives[1], objectives[2]
+        )?;
+    }
+
+    for (index, (individual, objectives)) in front.iter().enumerate() {
+        writeln!(
+            file,
+            "\nindividual {}: collisions {}, length {}, segments {}",
+            index, objectives[0], objectives[1], objectives[2]
+        )?;
+        for (net, corners) in individual.polylines().iter().enumerate() {
+            let corners: Vec<String> = corners
+                .iter()
+                .map(|corner| format!("{};{}", corner.0, corner.1))
+                .collect();
+            writeln!(file, "net {}: {}", net, corners.join(" "))?;
+        }
+    }
+
+    file.flush()
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,37 @@
+use super::*;
+use crate::problem::fixtures::crossing_problem;
+use crate::problem::GaConfig;
+
+#[test]
+fn test_non_dominated_sort() {
+    let objectives = [[0, 10, 3], [1, 5, 2], [1, 10, 3], [2, 12, 4], [0, 10, 3]];
+    let fronts = non_dominated_sort(&objectives);
+    assert_eq!(fronts, vec![vec![0, 1, 4], vec![2], vec![3]]);
+
+    let distance = crowding_distance(&fronts[0], &objectives);
+    assert!(distance.iter().any(|d| d.is_infinite()));
+    assert!(distance.iter().all(|d| *d >= 0.));
+}
+
+#[test]
+fn test_nsga2_front_is_non_dominated() {
+    let mut problem = crossing_problem(
+        3,
+        GaConfig {
+            population: 30,
+            iterations: 10,
+            ..Default::default()
+        },
+    );
+    let front = problem.nsga2_search(Some(3));
+    assert!(!front.is_empty());
+    for (individual, objectives) in &front {
+        assert_eq!(*objectives, super::objectives(individual));
+        assert!(front.iter().all(|(_, other)| {
+            !(other.iter().zip(objectives).all(|(a, b)| a <= b) && other != objectives)
+        }));
+        let polylines = individual.polylines();
+        assert_eq!(polylines[0].first(), Some(&(1, 1)));
+        assert_eq!(polylines[0].last(), Some(&(4, 4)));
+    }
+}
@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+thread_local! {
+    static OCCUPANCY: RefCell<OccupancyGrid> = RefCell::new(OccupancyGrid::default());
+    static TRACKED: RefCell<TrackedOccupancy> = RefCell::new(TrackedOccupancy::default());
+}
+
+static NEXT_ROUTES: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies the routes of an individual. Every change gets a new version while clones keep
+/// that of the individual they were cloned from, so equal versions always mean equal routes.
+pub type RoutesVersion = u64;
+
+/// A version of routes no thread has tracked the occupancy of yet.
+pub fn new_routes() -> RoutesVersion {
+    NEXT_ROUTES.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Dense per-cell occupancy of a board.
+///
+/// Instead of clearing the grid between evaluations every cell stores the epoch in which it was
+/// last occupied, so starting a new evaluation is just an epoch bump.
+#[derive(Default)]
+pub struct OccupancyGrid {
+    stamps: Vec<u32>,
+    columns: usize,
+    epoch: u32,
+}
+
+impl OccupancyGrid {
+    fn reset(&mut self, dimensions: (u32, u32)) {
+        let size = dimensions.0 as usize * dimensions.1 as usize;
+        if self.stamps.len() < size {
+            self.stamps.resize(size, 0);
+        }
+        self.columns = dimensions.1 as usize;
+
+        self.epoch = self.epoch.wrapping_add(1);
+        if self.epoch == 0 {
+            self.stamps.iter_mut().for_each(|stamp| *stamp = 0);
+            self.epoch = 1;
+        }
+    }
+
+    /// Marks `point` as occupied, returning whether it already was in this epoch.
+    pub fn occupy(&mut self, point: (u32, u32)) -> bool {
+        let stamp = &mut self.stamps[point.0 as usize * self.columns + point.1 as usize];
+        let occupied = *stamp == self.epoch;
+        *stamp = self.epoch;
+        occupied
+    }
+}
+
+/// Runs `f` with the calling thread's occupancy grid, emptied and sized for `dimensions`.
+pub fn with_occupancy<T>(dimensions: (u32, u32), f: impl FnOnce(&mut OccupancyGrid) -> T) -> T {
+    OCCUPANCY.with(|grid| {
+        let mut grid = grid.borrow_mut();
+        grid.reset(dimensions);
+        f(&mut grid)
+    })
+}
+
+/// Number of traces passing through every cell of a board, kept for the individual a thread last
+/// changed so its collisions can be updated when only some of its connections change.
+#[derive(Clone, Debug)]
+pub struct OccupancyCounts {
+    counts: Vec<u16>,
+    columns: usize,
+    collisions: u32,
+}
+
+impl OccupancyCounts {
+    pub fn new(dimensions: (u32, u32)) -> OccupancyCounts {
+        OccupancyCounts {
+            counts: vec![0; dimensions.0 as usize * dimensions.1 as usize],
+            columns: dimensions.1 as usize,
+            collisions: 0,
+        }
+    }
+
+    fn clear(&mut self, dimensions: (u32, u32)) {
+        self.counts.clear();
+        self.counts
+            .resize(dimensions.0 as usize * dimensions.1 as usize, 0);
+        self.columns = dimensions.1 as usize;
+        self.collisions = 0;
+    }
+
+    pub fn collisions(&self) -> u32 {
+        self.collisions
+    }
+
+    pub fn count(&self, point: (u32, u32)) -> u16 {
+        self.counts[point.0 as usize * self.columns + point.1 as usize]
+    }
+
+    pub fn add(&mut self, point: (u32, u32)) {
+        let count = &mut self.counts[point.0 as usize * self.columns + point.1 as usize];
+        if *count > 0 {
+            self.collisions += 1;
+        }
+        *count += 1;
+    }
+
+    pub fn remove(&mut self, point: (u32, u32)) {
+        let count = &mut self.counts[point.0 as usize * self.columns + point.1 as usize];
+        *count -= 1;
+        if *count > 0 {
+            self.collisions -= 1;
+        }
+    }
+}
+
+#[derive(Default)]
+struct TrackedOccupancy {
+    routes: Option<RoutesVersion>,
+    counts: Option<OccupancyCounts>,
+}
+
+/// Runs `f` with the calling thread's occupancy counts of the routes `routes`, which afterwards
+/// are those of `next`. A thread tracks a single version of routes at a time, so the counts are
+/// handed over emptied, with `f` told to fill them, whenever it last tracked some other routes;
+/// that way individuals don't each carry a buffer the size of the board. A clone shares the
+/// version of its parent, so whichever of the two changes first takes the counts over.
+pub fn with_tracked_occupancy<T>(
+    routes: RoutesVersion,
+    next: RoutesVersion,
+    dimensions: (u32, u32),
+    f: impl FnOnce(&mut OccupancyCounts, bool) -> T,
+) -> T {
+    TRACKED.with(|tracked| {
+        let mut tracked = tracked.borrow_mut();
+        let fresh = tracked.routes != Some(routes);
+        tracked.routes = None;
+        let counts = tracked
+            .counts
+            .get_or_insert_with(|| OccupancyCounts::new(dimensions));
+        if fresh {
+            counts.clear(dimensions);
+        }
+        let result = f(counts, fresh);
+        tracked.routes = Some(next);
+        result
+    })
+}
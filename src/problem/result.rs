@@ -0,0 +1,225 @@
+//! Serialisable outcome of a search run, and loading the individual of a saved one back as a
+//! solution of a problem.
+
+use super::adaptation::Rates;
+use super::annealing::{AnnealingConfig, AnnealingStep};
+use super::config::GaConfig;
+use super::individual::{Connection, Direction, FitnessBreakdown, Individual};
+use super::island::IslandConfig;
+use super::Problem;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+/// Parameters a `SearchResult` was produced with.
+#[derive(Clone, Debug, Serialize)]
+pub enum SearchConfig {
+    /// `Problem::genetic_search`.
+    Genetic(GaConfig),
+    /// `Problem::island_search`.
+    Island {
+        /// GA parameters shared by all islands.
+        ga: GaConfig,
+        /// Parameters of the island model.
+        islands: IslandConfig,
+    },
+    /// `Problem::random_search`.
+    Random {
+        /// Random individuals sampled.
+        iterations: u64,
+        /// Threads the samples were split over.
+        threads: usize,
+    },
+    /// `Problem::simulated_annealing`.
+    Annealing(AnnealingConfig),
+}
+
+/// Best individual of a search run, with what is needed to compare or reproduce the run.
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchResult {
+    /// Best individual found.
+    pub individual: Individual,
+    /// Fitness of `individual`.
+    pub fitness: FitnessBreakdown,
+    /// Wall-clock time of the run.
+    pub elapsed: Duration,
+    /// Individuals generated or bred, and scored, during the run.
+    pub evaluations: u64,
+    /// Generations bred, 0 for searches without generations.
+    pub generations: u32,
+    /// Seed the run was started with, if any.
+    pub seed: Option<u64>,
+    /// Parameters of the run.
+    pub config: SearchConfig,
+    /// Progress recorded during the run.
+    pub history: SearchHistory,
+}
+
+/// Progress a search recorded along the way.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub enum SearchHistory {
+    /// The search records no progress.
+    #[default]
+    None,
+    /// One entry per generation of `Problem::genetic_search`.
+    Generations(Vec<GenerationStats>),
+    /// One entry per epoch of `Problem::island_search`.
+    Epochs(Vec<EpochStats>),
+    /// Steps recorded every `AnnealingConfig::trace_every` steps of every chain of
+    /// `Problem::simulated_annealing`.
+    Annealing(Vec<AnnealingStep>),
+}
+
+/// State of `Problem::genetic_search` after breeding one generation.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GenerationStats {
+    /// Generations bred so far, this one included.
+    pub generation: u32,
+    /// Fitness of the best individual of the generation.
+    pub best: FitnessBreakdown,
+    /// Rates the generation was bred with.
+    pub rates: Rates,
+    /// Relative weights of `GaConfig::mutations` after the generation.
+    pub operator_weights: Vec<f32>,
+    /// Mean structural distance between individuals of the generation, see
+    /// `Problem::diversity`.
+    pub diversity: f32,
+}
+
+/// State of `Problem::island_search` at the end of one epoch, after the migration if any.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EpochStats {
+    /// Number of the epoch, from 1.
+    pub epoch: u32,
+    /// Generations bred by every island so far.
+    pub generation: u32,
+    /// Fitness of the best individual of every island.
+    pub island_bests: Vec<FitnessBreakdown>,
+}
+
+impl SearchResult {
+    /// Result with `individual` as the best individual and no history.
+    pub fn new(
+        individual: Individual,
+        elapsed: Duration,
+        evaluations: u64,
+        generations: u32,
+        seed: Option<u64>,
+        config: SearchConfig,
+    ) -> SearchResult {
+        SearchResult {
+            fitness: individual.fitness(),
+            individual,
+            elapsed,
+            evaluations,
+            generations,
+            seed,
+            config,
+            history: SearchHistory::None,
+        }
+    }
+
+    /// Attaches the progress the search recorded.
+    pub fn with_history(mut self, history: SearchHistory) -> SearchResult {
+        self.history = history;
+        self
+    }
+
+    /// The whole result as pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// The part of a saved `SearchResult` needed to restore its individual.
+#[derive(Deserialize)]
+struct SavedResult {
+    individual: SavedIndividual,
+}
+
+#[derive(Deserialize)]
+struct SavedIndividual {
+    connections: Vec<Connection>,
+    dimensions: (u32, u32),
+}
+
+/// Reads the individual of a `SearchResult` saved with `SearchResult::to_json`, see
+/// `parse_solution`.
+pub fn read_solution(path: &str, problem: &Problem) -> io::Result<Individual> {
+    parse_solution(&fs::read_to_string(path)?, problem)
+}
+
+/// Restores the individual of a `SearchResult` in JSON as a solution of `problem`. Fails unless
+/// it routes exactly the nets of `problem`, in order, on a board of the same size.
+pub fn parse_solution(text: &str, problem: &Problem) -> io::Result<Individual> {
+    let saved: SavedResult = serde_json::from_str(text)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let individual = saved.individual;
+    if individual.dimensions != problem.dimensions() {
+        return Err(invalid(format!(
+            "Solution is for a {:?} board, not {:?}",
+            individual.dimensions,
+            problem.dimensions()
+        )));
+    }
+    if individual.connections.len() != problem.pin_locations().len() {
+        return Err(invalid(format!(
+            "Solution routes {} nets, the board has {}",
+            individual.connections.len(),
+            problem.pin_locations().len()
+        )));
+    }
+
+    let mut paths = vec![];
+    for (index, (connection, pins)) in individual
+        .connections
+        .iter()
+        .zip(problem.pin_locations())
+        .enumerate()
+    {
+        if (connection.start(), connection.end()) != *pins {
+            return Err(invalid(format!(
+                "Net {} does not connect {:?} and {:?}",
+                index + 1,
+                pins.0,
+                pins.1
+            )));
+        }
+        let path = walk(connection, problem.dimensions()).ok_or_else(|| {
+            invalid(format!(
+                "Net {} leaves the board or misses its end pin",
+                index + 1
+            ))
+        })?;
+        paths.push(path);
+    }
+
+    Ok(Individual::from_paths(problem.dimensions(), &paths)
+        .with_obstacles(problem.obstacles().into()))
+}
+
+/// Cells `connection` runs through, if it stays on the board and ends on its end pin.
+fn walk(connection: &Connection, dimensions: (u32, u32)) -> Option<Vec<(u32, u32)>> {
+    let mut point = connection.start();
+    let mut path = vec![point];
+    for segment in connection.segments() {
+        for _ in 0..segment.length {
+            point = match segment.direction {
+                Direction::North => (point.0.checked_sub(1)?, point.1),
+                Direction::South => (point.0 + 1, point.1),
+                Direction::East => (point.0, point.1 + 1),
+                Direction::West => (point.0, point.1.checked_sub(1)?),
+            };
+            if point.0 >= dimensions.0 || point.1 >= dimensions.1 {
+                return None;
+            }
+            path.push(point);
+        }
+    }
+    (point == connection.end()).then_some(path)
+}
+
+#[cfg(test)]
+mod test;
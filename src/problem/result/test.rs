@@ -0,0 +1,93 @@
+use super::*;
+use crate::problem::fixtures::{crossing_problem, CROSSING_PINS};
+use crate::problem::{tournament_selection, GaConfig, ProblemBuilder};
+
+#[test]
+fn test_search_result_json() {
+    let mut problem = crossing_problem(
+        4,
+        GaConfig {
+            population: 10,
+            iterations: 3,
+            ..Default::default()
+        },
+    );
+    let result = problem.genetic_search(tournament_selection, Some(4));
+    assert_eq!(result.fitness, result.individual.fitness());
+    assert_eq!(result.generations, 3);
+    assert_eq!(result.evaluations, 40);
+
+    let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+    assert_eq!(json["fitness"]["collisions"], result.fitness.collisions);
+    assert_eq!(json["seed"], 4);
+    assert_eq!(json["config"]["Genetic"]["population"], 10);
+    assert_eq!(
+        json["individual"]["connections"].as_array().unwrap().len(),
+        2
+    );
+}
+
+#[test]
+fn test_genetic_search_history() {
+    let config = GaConfig {
+        population: 10,
+        iterations: 3,
+        ..Default::default()
+    };
+    let mut problem = crossing_problem(5, config.clone());
+    let result = problem.genetic_search(tournament_selection, Some(5));
+
+    let generations = match &result.history {
+        SearchHistory::Generations(generations) => generations,
+        history => panic!("Expected generation stats, got {:?}", history),
+    };
+    let numbers: Vec<u32> = generations.iter().map(|stats| stats.generation).collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    assert_eq!(generations[2].best, result.fitness);
+    for stats in generations {
+        assert_eq!(stats.rates.mutation, config.mutation_rate);
+        assert_eq!(stats.operator_weights.len(), config.mutations.len());
+        assert!(stats.diversity >= 0.);
+    }
+    assert_eq!(generations[2].diversity, problem.diversity());
+
+    let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+    assert_eq!(json["history"]["Generations"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn test_parse_solution_round_trip() {
+    let mut problem = crossing_problem(
+        6,
+        GaConfig {
+            population: 10,
+            iterations: 2,
+            ..Default::default()
+        },
+    );
+    let result = problem.genetic_search(tournament_selection, Some(6));
+    let json = result.to_json().unwrap();
+
+    let individual = parse_solution(&json, &problem).unwrap();
+    assert_eq!(individual.fitness(), result.fitness);
+    assert_eq!(individual.distance(&result.individual), 0.);
+
+    let other = crossing_problem(6, GaConfig::default());
+    let reversed = ProblemBuilder::new((6, 6))
+        .net(CROSSING_PINS[1].0, CROSSING_PINS[1].1)
+        .net(CROSSING_PINS[0].0, CROSSING_PINS[0].1)
+        .build()
+        .unwrap();
+    let larger = ProblemBuilder::new((7, 6))
+        .net(CROSSING_PINS[0].0, CROSSING_PINS[0].1)
+        .net(CROSSING_PINS[1].0, CROSSING_PINS[1].1)
+        .build()
+        .unwrap();
+    assert!(parse_solution(&json, &other).is_ok());
+    assert!(parse_solution(&json, &reversed).is_err());
+    assert!(parse_solution(&json, &larger).is_err());
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["individual"]["connections"][0]["segments"][0]["length"] = 100.into();
+    assert!(parse_solution(&value.to_string(), &problem).is_err());
+}
@@ -0,0 +1,199 @@
+//! A* routing of one net at a time, minimising length, bends and the cost of the cells used.
+
+use super::{cell, neighbors};
+use crate::problem::individual::{Individual, PinPair, WEIGHTS};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Order in which sequential routers lay down the nets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NetOrder {
+    /// The order of the board file.
+    AsGiven,
+    /// Nets with the smallest pin distance first.
+    ShortestFirst,
+    /// Nets with the largest pin distance first.
+    LongestFirst,
+    /// Nets with the smallest pin bounding box first.
+    BoundingBoxArea,
+}
+
+impl NetOrder {
+    /// Indices of `pin_locations` in routing order.
+    pub fn order(&self, pin_locations: &[PinPair]) -> Vec<usize> {
+        let span = |index: usize| {
+            let (start, end) = pin_locations[index];
+            (
+                (start.0 as i64 - end.0 as i64).abs(),
+                (start.1 as i64 - end.1 as i64).abs(),
+            )
+        };
+
+        let mut order: Vec<usize> = (0..pin_locations.len()).collect();
+        match self {
+            NetOrder::AsGiven => {}
+            NetOrder::ShortestFirst => order.sort_by_key(|&index| {
+                let (rows, columns) = span(index);
+                rows + columns
+            }),
+            NetOrder::LongestFirst => order.sort_by_key(|&index| {
+                let (rows, columns) = span(index);
+                -(rows + columns)
+            }),
+            NetOrder::BoundingBoxArea => order.sort_by_key(|&index| {
+                let (rows, columns) = span(index);
+                (rows + 1) * (columns + 1)
+            }),
+        }
+        order
+    }
+}
+
+/// Routes the nets one after another in `order`, each along the path that adds the least to
+/// `Individual::evaluate`: every step costs its length weight, every new segment its segment
+/// weight and every step onto a cell already used by a pin or an earlier trace a collision.
+pub fn astar_route(
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+    obstacles: &[(u32, u32)],
+    order: NetOrder,
+) -> Individual {
+    let mut occupancy = vec![0u32; dimensions.0 as usize * dimensions.1 as usize];
+    for obstacle in obstacles {
+        occupancy[cell(*obstacle, dimensions)] += 1;
+    }
+    for (start, end) in pin_locations {
+        occupancy[cell(*start, dimensions)] += 1;
+        occupancy[cell(*end, dimensions)] += 1;
+    }
+
+    let mut paths = vec![vec![]; pin_locations.len()];
+    for index in order.order(pin_locations) {
+        let (start, end) = pin_locations[index];
+        occupancy[cell(start, dimensions)] -= 1;
+        occupancy[cell(end, dimensions)] -= 1;
+
+        let path = search(start, end, dimensions, |point| {
+            if occupancy[cell(point, dimensions)] > 0 {
+                WEIGHTS.0
+            } else {
+                0.
+            }
+        });
+        for point in &path {
+            occupancy[cell(*point, dimensions)] += 1;
+        }
+        paths[index] = path;
+    }
+
+    Individual::from_paths(dimensions, &paths).with_obstacles(obstacles.into())
+}
+
+/// No heading yet, used for the start pin.
+const NO_HEADING: usize = 4;
+
+#[derive(PartialEq)]
+struct Node {
+    estimate: f32,
+    cost: f32,
+    point: (u32, u32),
+    heading: usize,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Cheapest path from `start` to `end` in length, bends and `cell_cost` of every cell entered.
+pub(super) fn search(
+    start: (u32, u32),
+    end: (u32, u32),
+    dimensions: (u32, u32),
+    cell_cost: impl Fn((u32, u32)) -> f32,
+) -> Vec<(u32, u32)> {
+    let state = |point: (u32, u32), heading: usize| cell(point, dimensions) * 5 + heading;
+    let mut best = vec![f32::INFINITY; dimensions.0 as usize * dimensions.1 as usize * 5];
+    let mut previous: Vec<Option<((u32, u32), usize)>> = vec![None; best.len()];
+    let mut queue = BinaryHeap::new();
+
+    best[state(start, NO_HEADING)] = 0.;
+    queue.push(Node {
+        estimate: heuristic(start, end),
+        cost: 0.,
+        point: start,
+        heading: NO_HEADING,
+    });
+
+    while let Some(node) = queue.pop() {
+        if node.point == end {
+            let mut path = vec![end];
+            let mut current = (node.point, node.heading);
+            while let Some(before) = previous[state(current.0, current.1)] {
+                path.push(before.0);
+                current = before;
+            }
+            path.reverse();
+            return path;
+        }
+        if node.cost > best[state(node.point, node.heading)] {
+            continue;
+        }
+
+        for next in neighbors(node.point, dimensions) {
+            let heading = heading(node.point, next);
+            let mut cost = node.cost + WEIGHTS.1;
+            if heading != node.heading {
+                cost += WEIGHTS.2;
+            }
+            if next != end {
+                cost += cell_cost(next);
+            }
+
+            let next_state = state(next, heading);
+            if cost < best[next_state] {
+                best[next_state] = cost;
+                previous[next_state] = Some((node.point, node.heading));
+                queue.push(Node {
+                    estimate: cost + heuristic(next, end),
+                    cost,
+                    point: next,
+                    heading,
+                });
+            }
+        }
+    }
+
+    unreachable!("Board is connected")
+}
+
+/// Lower bound of the remaining cost: the Manhattan distance, and one more segment unless the
+/// pin lies on a straight line.
+fn heuristic(point: (u32, u32), end: (u32, u32)) -> f32 {
+    let rows = (point.0 as i64 - end.0 as i64).abs();
+    let columns = (point.1 as i64 - end.1 as i64).abs();
+    let bends = if rows > 0 && columns > 0 { 1. } else { 0. };
+    (rows + columns) as f32 * WEIGHTS.1 + bends * WEIGHTS.2
+}
+
+/// Index of the direction from `from` to the neighbouring `to`, in `neighbors` order.
+fn heading(from: (u32, u32), to: (u32, u32)) -> usize {
+    if to.0 < from.0 {
+        0
+    } else if to.0 > from.0 {
+        1
+    } else if to.1 > from.1 {
+        2
+    } else {
+        3
+    }
+}
@@ -0,0 +1,73 @@
+//! Lee maze routing: the shortest path of every net found with a breadth-first wavefront.
+
+use super::{cell, neighbors, trace_back};
+use crate::problem::individual::{Individual, PinPair};
+use std::collections::VecDeque;
+
+/// Routes the nets one after another with a breadth-first wavefront, treating the pins of other
+/// nets, `obstacles` and the traces of already routed nets as blocked.
+///
+/// A net that cannot be routed around the existing traces is routed through them, and if even the
+/// other pins and obstacles wall it in, through those too, so the result always connects every
+/// net and any conflicts show up as collisions.
+pub fn lee_route(
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+    obstacles: &[(u32, u32)],
+) -> Individual {
+    let mut pins = vec![false; dimensions.0 as usize * dimensions.1 as usize];
+    for obstacle in obstacles {
+        pins[cell(*obstacle, dimensions)] = true;
+    }
+    for (start, end) in pin_locations {
+        pins[cell(*start, dimensions)] = true;
+        pins[cell(*end, dimensions)] = true;
+    }
+    let mut traces = pins.clone();
+
+    let mut paths = vec![];
+    for &(start, end) in pin_locations {
+        let path = wavefront(start, end, dimensions, &traces)
+            .or_else(|| wavefront(start, end, dimensions, &pins))
+            .or_else(|| wavefront(start, end, dimensions, &[]))
+            .expect("Board is not connected");
+        for point in &path {
+            traces[cell(*point, dimensions)] = true;
+        }
+        paths.push(path);
+    }
+
+    Individual::from_paths(dimensions, &paths).with_obstacles(obstacles.into())
+}
+
+/// Shortest path from `start` to `end` avoiding `blocked` cells. An empty `blocked` blocks nothing.
+pub(crate) fn wavefront(
+    start: (u32, u32),
+    end: (u32, u32),
+    dimensions: (u32, u32),
+    blocked: &[bool],
+) -> Option<Vec<(u32, u32)>> {
+    let mut previous = vec![None; dimensions.0 as usize * dimensions.1 as usize];
+    let mut visited = vec![false; previous.len()];
+    let mut queue = VecDeque::new();
+
+    visited[cell(start, dimensions)] = true;
+    queue.push_back(start);
+
+    while let Some(point) = queue.pop_front() {
+        if point == end {
+            return Some(trace_back(end, &previous, dimensions));
+        }
+        for next in neighbors(point, dimensions) {
+            let index = cell(next, dimensions);
+            let free = next == end || !blocked.get(index).copied().unwrap_or(false);
+            if free && !visited[index] {
+                visited[index] = true;
+                previous[index] = Some(point);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,44 @@
+//! Deterministic routers laying the nets down one after another: the Lee maze router, A* with
+//! bend and collision costs, and the PathFinder negotiated-congestion router built on A*.
+
+pub mod astar;
+pub mod lee;
+pub mod pathfinder;
+
+pub use astar::{astar_route, NetOrder};
+pub use lee::lee_route;
+pub use pathfinder::{pathfinder_route, PathFinderConfig, PathFinderIteration, PathFinderResult};
+
+/// Index of `point` in a row-major grid of `dimensions`.
+pub(crate) fn cell(point: (u32, u32), dimensions: (u32, u32)) -> usize {
+    point.0 as usize * dimensions.1 as usize + point.1 as usize
+}
+
+/// Cells next to `point`, in north, south, east, west order.
+fn neighbors(point: (u32, u32), dimensions: (u32, u32)) -> impl Iterator<Item = (u32, u32)> {
+    let (row, column) = point;
+    IntoIterator::into_iter([
+        (row > 0).then(|| (row - 1, column)),
+        (row + 1 < dimensions.0).then(|| (row + 1, column)),
+        (column + 1 < dimensions.1).then(|| (row, column + 1)),
+        (column > 0).then(|| (row, column - 1)),
+    ])
+    .flatten()
+}
+
+/// Walks the predecessor links back from `end` to the pin the search started from.
+fn trace_back(
+    end: (u32, u32),
+    previous: &[Option<(u32, u32)>],
+    dimensions: (u32, u32),
+) -> Vec<(u32, u32)> {
+    let mut path = vec![end];
+    while let Some(point) = previous[cell(*path.last().unwrap(), dimensions)] {
+        path.push(point);
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod test;
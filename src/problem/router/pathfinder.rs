@@ -0,0 +1,156 @@
+//! PathFinder: nets are rerouted with A* over and over, with shared cells getting more expensive
+//! every pass until the nets stop fighting over them.
+
+use super::astar::{search, NetOrder};
+use super::cell;
+use crate::problem::individual::{Individual, PinPair, WEIGHTS};
+use std::sync::Arc;
+
+/// Parameters of `pathfinder_route`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathFinderConfig {
+    /// Maximum number of rip-up-and-reroute passes over the nets. At least one pass always runs.
+    pub iterations: u32,
+    /// Cost of sharing a cell with one other trace in the first pass, in steps.
+    pub present_factor: f32,
+    /// Factor the present congestion cost grows by after every pass.
+    pub present_growth: f32,
+    /// Cost added permanently to a cell for every extra trace on it after a pass, in steps.
+    pub history_factor: f32,
+    /// Order in which every pass reroutes the nets.
+    pub order: NetOrder,
+}
+
+impl Default for PathFinderConfig {
+    fn default() -> Self {
+        PathFinderConfig {
+            iterations: 50,
+            present_factor: 0.5,
+            present_growth: 1.5,
+            history_factor: 0.2,
+            order: NetOrder::ShortestFirst,
+        }
+    }
+}
+
+/// State of the routing after one pass.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathFinderIteration {
+    /// Number of the pass, from 0.
+    pub iteration: u32,
+    /// Nets ripped up and routed again in the pass.
+    pub rerouted: usize,
+    /// See `Individual::collisions`.
+    pub collisions: u32,
+    /// Cells used by more than one trace after the pass.
+    pub overused_cells: usize,
+    /// See `Individual::evaluate`.
+    pub fitness: f32,
+    /// Congestion cost factor the pass routed with.
+    pub present_factor: f32,
+}
+
+/// Outcome of `pathfinder_route`.
+pub struct PathFinderResult {
+    /// Best routing found over all passes.
+    pub individual: Individual,
+    /// State after every pass made.
+    pub iterations: Vec<PathFinderIteration>,
+}
+
+/// Negotiated-congestion router. Every pass rips up and reroutes each net that shares a cell with
+/// another one, using the A* search of `astar_route` with cells priced by how many traces
+/// currently use them and how congested they have been in earlier passes. Stops as soon as a pass
+/// ends without collisions or after `config.iterations` passes.
+pub fn pathfinder_route(
+    dimensions: (u32, u32),
+    pin_locations: &[PinPair],
+    obstacles: &[(u32, u32)],
+    config: &PathFinderConfig,
+) -> PathFinderResult {
+    let cells = dimensions.0 as usize * dimensions.1 as usize;
+    let mut occupancy = vec![0u32; cells];
+    let mut history = vec![0f32; cells];
+    for obstacle in obstacles {
+        occupancy[cell(*obstacle, dimensions)] += 1;
+    }
+    let obstacles: Arc<[(u32, u32)]> = obstacles.into();
+    for (start, end) in pin_locations {
+        occupancy[cell(*start, dimensions)] += 1;
+        occupancy[cell(*end, dimensions)] += 1;
+    }
+
+    let order = config.order.order(pin_locations);
+    let mut paths: Vec<Vec<(u32, u32)>> = vec![vec![]; pin_locations.len()];
+    let mut present_factor = config.present_factor;
+    let mut best: Option<(Individual, f32)> = None;
+    let mut iterations = vec![];
+
+    for iteration in 0..config.iterations.max(1) {
+        let mut rerouted = 0;
+        for &index in &order {
+            let (start, end) = pin_locations[index];
+            let path = &paths[index];
+            let congested = path.is_empty()
+                || path
+                    .iter()
+                    .any(|point| occupancy[cell(*point, dimensions)] > 1);
+            if !congested {
+                continue;
+            }
+
+            if path.is_empty() {
+                occupancy[cell(start, dimensions)] -= 1;
+                occupancy[cell(end, dimensions)] -= 1;
+            } else {
+                for point in path {
+                    occupancy[cell(*point, dimensions)] -= 1;
+                }
+            }
+
+            let path = search(start, end, dimensions, |point| {
+                let index = cell(point, dimensions);
+                WEIGHTS.1 * (history[index] + present_factor * occupancy[index] as f32)
+            });
+            for point in &path {
+                occupancy[cell(*point, dimensions)] += 1;
+            }
+            paths[index] = path;
+            rerouted += 1;
+        }
+
+        let mut overused_cells = 0;
+        for (count, cost) in occupancy.iter().zip(history.iter_mut()) {
+            if *count > 1 {
+                overused_cells += 1;
+                *cost += config.history_factor * (*count - 1) as f32;
+            }
+        }
+
+        let individual =
+            Individual::from_paths(dimensions, &paths).with_obstacles(obstacles.clone());
+        let collisions = individual.collisions();
+        let fitness = individual.evaluate();
+        iterations.push(PathFinderIteration {
+            iteration,
+            rerouted,
+            collisions,
+            overused_cells,
+            fitness,
+            present_factor,
+        });
+        if best.as_ref().is_none_or(|best| fitness < best.1) {
+            best = Some((individual, fitness));
+        }
+
+        if collisions == 0 {
+            break;
+        }
+        present_factor *= config.present_growth;
+    }
+
+    PathFinderResult {
+        individual: best.expect("at least one pass runs").0,
+        iterations,
+    }
+}
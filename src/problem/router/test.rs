@@ -0,0 +1,123 @@
+use super::*;
+use crate::problem::fixtures::assert_valid_individual;
+use crate::problem::load_problem;
+
+#[test]
+fn test_lee_route_bundled_boards() {
+    for name in &["zad0", "zad1", "zad2", "zad3"] {
+        let problem = load_problem(&format!("test_data/{}.txt", name), None);
+        let (rows, columns) = problem.dimensions();
+        let pins: Vec<_> = problem
+            .pin_locations()
+            .iter()
+            .flat_map(|&(start, end)| IntoIterator::into_iter([start, end]))
+            .collect();
+        // A wall across the middle row, open at both sides.
+        let obstacles: Vec<_> = (1..columns - 1)
+            .map(|column| (rows / 2, column))
+            .filter(|cell| !pins.contains(cell))
+            .collect();
+
+        let individual = lee_route(problem.dimensions(), problem.pin_locations(), &obstacles);
+        assert_valid_individual(&individual, problem.dimensions(), problem.pin_locations());
+        for connection in individual.connections() {
+            assert!(
+                connection
+                    .following_points()
+                    .iter()
+                    .all(|point| !obstacles.contains(point)),
+                "{} routes through an obstacle",
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_lee_route_avoids_traces() {
+    let pins = vec![((0, 1), (2, 1)), ((1, 0), (1, 2))];
+    let individual = lee_route((4, 4), &pins, &[]);
+    assert_eq!(individual.collisions(), 0);
+    assert_eq!(individual.evaluate(), 8. * 0.2 + 4. * 0.1);
+}
+
+#[test]
+fn test_astar_route_minimizes_bends() {
+    let individual = astar_route((5, 5), &[((0, 0), (4, 4))], &[], NetOrder::AsGiven);
+    assert_eq!(individual.evaluate(), 8. * 0.2 + 2. * 0.1);
+}
+
+#[test]
+fn test_astar_route_bundled_boards() {
+    for name in &["zad0", "zad1", "zad2", "zad3"] {
+        let problem = load_problem(&format!("test_data/{}.txt", name), None);
+        let lee = lee_route(problem.dimensions(), problem.pin_locations(), &[]);
+        // Each net is routed greedily, so a single order can lose to Lee's detours (zad0 and zad3
+        // in the board file order), but the best of the orders never does.
+        let best = IntoIterator::into_iter([
+            NetOrder::AsGiven,
+            NetOrder::ShortestFirst,
+            NetOrder::LongestFirst,
+            NetOrder::BoundingBoxArea,
+        ])
+        .map(|order| {
+            let individual = astar_route(problem.dimensions(), problem.pin_locations(), &[], order);
+            assert_valid_individual(&individual, problem.dimensions(), problem.pin_locations());
+            individual.evaluate()
+        })
+        .fold(f32::INFINITY, f32::min);
+        assert!(
+            best <= lee.evaluate(),
+            "{}: A* {} is worse than Lee {}",
+            name,
+            best,
+            lee.evaluate()
+        );
+    }
+}
+
+#[test]
+fn test_pathfinder_route_bundled_boards() {
+    for name in &["zad0", "zad1", "zad2", "zad3"] {
+        let problem = load_problem(&format!("test_data/{}.txt", name), None);
+        let config = PathFinderConfig::default();
+        let result = pathfinder_route(problem.dimensions(), problem.pin_locations(), &[], &config);
+        assert_eq!(
+            result.individual.connection_count(),
+            problem.pin_locations().len()
+        );
+        assert!(!result.iterations.is_empty());
+        assert!(result.iterations.len() <= config.iterations as usize);
+        let best = result
+            .iterations
+            .iter()
+            .map(|iteration| iteration.fitness)
+            .fold(f32::INFINITY, f32::min);
+        assert_eq!(result.individual.evaluate(), best);
+        let collisions: Vec<u32> = result
+            .iterations
+            .iter()
+            .map(|iteration| iteration.collisions)
+            .collect();
+        // Negotiation may trade collisions back and forth between passes, it only has to end
+        // up no worse than it started.
+        assert!(
+            collisions.last() <= collisions.first(),
+            "{}: collisions rose over the passes {:?}",
+            name,
+            collisions
+        );
+    }
+}
+
+#[test]
+fn test_pathfinder_route_runs_at_least_one_pass() {
+    let pins = vec![((0, 1), (2, 1)), ((1, 0), (1, 2))];
+    let config = PathFinderConfig {
+        iterations: 0,
+        ..Default::default()
+    };
+    let result = pathfinder_route((4, 4), &pins, &[], &config);
+    assert_eq!(result.iterations.len(), 1);
+    assert_valid_individual(&result.individual, (4, 4), &pins);
+}
@@ -0,0 +1,572 @@
+//! Interop with other autorouter front ends through the Specctra formats: boards are imported from
+//! a DSN design and routes are exported as an SES session.
+//!
+//! The design is snapped onto a grid of `pitch` millimetre cells covering the bounding box of its
+//! boundary, with cell `(0, 0)` in the top left corner as the y axis of DSN points up. Every net
+//! with exactly two pins becomes a net of the problem unless one of its pads lands on the same
+//! cell as another pin, while the pads of the other nets and all keepouts become obstacles.
+
+use super::builder::{BuildError, ProblemBuilder};
+use super::{Individual, Problem};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+/// Coordinates of SES sessions are written in tenths of a micrometre.
+const SES_RESOLUTION: f64 = 10_000.;
+
+/// What `write_ses` needs to know about the design a problem was imported from.
+#[derive(Clone, Debug)]
+pub struct DsnDesign {
+    /// Nets the problem leaves out: those without exactly two pins and those with a pad on the
+    /// same grid cell as another pad of a two-pin net.
+    pub skipped: Vec<String>,
+    /// Layer the wires are placed on, the first layer of the design.
+    pub layer: String,
+    /// Wire width in millimetres, taken from the width rule of the design or half the pitch.
+    pub width: f64,
+    name: String,
+    nets: Vec<String>,
+    /// Positions of the two pads of every net of the problem.
+    pads: Vec<((f64, f64), (f64, f64))>,
+    grid: Grid,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Grid {
+    left: f64,
+    top: f64,
+    pitch: f64,
+}
+
+impl Grid {
+    fn cell(&self, (x, y): (f64, f64)) -> (i64, i64) {
+        (
+            ((self.top - y) / self.pitch).floor() as i64,
+            ((x - self.left) / self.pitch).floor() as i64,
+        )
+    }
+
+    fn centre(&self, (row, column): (u32, u32)) -> (f64, f64) {
+        (
+            self.left + (column as f64 + 0.5) * self.pitch,
+            self.top - (row as f64 + 0.5) * self.pitch,
+        )
+    }
+}
+
+/// Reads the DSN design at `path`, see `parse_dsn`.
+pub fn read_dsn(path: &str, pitch: f64, seed: Option<u64>) -> io::Result<(Problem, DsnDesign)> {
+    parse_dsn(&fs::read_to_string(path)?, pitch, seed)
+}
+
+/// Imports the boundary, pads, nets and keepouts of a DSN design into a `Problem` seeded with
+/// `seed` on a grid of `pitch` millimetres.
+pub fn parse_dsn(text: &str, pitch: f64, seed: Option<u64>) -> io::Result<(Problem, DsnDesign)> {
+    if pitch.is_nan() || pitch <= 0. {
+        return Err(invalid("The pitch must be positive"));
+    }
+    let pcb = parse(text)?;
+    if pcb.keyword() != Some("pcb") {
+        return Err(invalid("Not a DSN design"));
+    }
+    let unit = pcb
+        .list("unit")
+        .or_else(|| pcb.list("resolution"))
+        .and_then(|unit| unit.atoms().first().copied())
+        .unwrap_or("inch");
+    let scale = unit_mm(unit).ok_or_else(|| invalid(format!("Unknown unit {}", unit)))?;
+
+    let structure = pcb
+        .list("structure")
+        .ok_or_else(|| invalid("Missing structure"))?;
+    let boundary = structure
+        .lists("boundary")
+        .flat_map(Expr::shapes)
+        .map(|shape| shape.scaled(scale).bounds())
+        .reduce(|(min1, max1), (min2, max2)| {
+            (
+                (min1.0.min(min2.0), min1.1.min(min2.1)),
+                (max1.0.max(max2.0), max1.1.max(max2.1)),
+            )
+        });
+    let ((left, bottom), (right, top)) = boundary.ok_or_else(|| invalid("Missing boundary"))?;
+    let grid = Grid { left, top, pitch };
+    let dimensions = (
+        ((top - bottom) / pitch).floor() as u32,
+        ((right - left) / pitch).floor() as u32,
+    );
+    if dimensions.0 == 0 || dimensions.1 == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            BuildError::EmptyBoard,
+        ));
+    }
+    let snap = |point: (f64, f64)| {
+        let (row, column) = grid.cell(point);
+        (
+            row.clamp(0, dimensions.0 as i64 - 1) as u32,
+            column.clamp(0, dimensions.1 as i64 - 1) as u32,
+        )
+    };
+
+    let layer = structure
+        .lists("layer")
+        .find_map(|layer| layer.atoms().first().copied())
+        .ok_or_else(|| invalid("Missing layers"))?
+        .to_string();
+    let width = structure
+        .lists("rule")
+        .filter_map(|rule| rule.list("width"))
+        .find_map(|width| width.numbers().first().copied())
+        .map_or(pitch / 2., |width| width * scale);
+
+    let pads = pad_positions(&pcb, scale);
+    let mut builder = ProblemBuilder::new(dimensions);
+    let mut design = DsnDesign {
+        skipped: vec![],
+        layer,
+        width,
+        name: pcb.atoms().first().copied().unwrap_or("board").to_string(),
+        nets: vec![],
+        pads: vec![],
+        grid,
+    };
+    let mut candidates = vec![];
+    if let Some(network) = pcb.list("network") {
+        for net in network.lists("net") {
+            let name = net
+                .atoms()
+                .first()
+                .copied()
+                .ok_or_else(|| invalid("Net without a name"))?;
+            let pins: Vec<&str> = net.list("pins").map_or(vec![], Expr::atoms);
+            match pins.as_slice() {
+                [start, end] => {
+                    candidates.push((name, pins.clone(), (pad(&pads, start)?, pad(&pads, end)?)))
+                }
+                _ => design.skipped.push(name.to_string()),
+            }
+        }
+    }
+
+    // Pads snapping onto the same cell can't both be pins, so all nets involved are skipped.
+    let mut uses: HashMap<(u32, u32), usize> = HashMap::new();
+    for (_, _, (start, end)) in &candidates {
+        *uses.entry(snap(*start)).or_default() += 1;
+        *uses.entry(snap(*end)).or_default() += 1;
+    }
+    let mut routed = HashSet::new();
+    for (name, pins, ends) in candidates {
+        if uses[&snap(ends.0)] > 1 || uses[&snap(ends.1)] > 1 {
+            design.skipped.push(name.to_string());
+            continue;
+        }
+        routed.extend(pins);
+        builder = builder.named_net(name, snap(ends.0), snap(ends.1));
+        design.nets.push(name.to_string());
+        design.pads.push(ends);
+    }
+
+    let pins: HashSet<(u32, u32)> = design
+        .pads
+        .iter()
+        .flat_map(|&(start, end)| vec![snap(start), snap(end)])
+        .collect();
+    let mut obstacles = BTreeSet::new();
+    for (pin, &position) in &pads {
+        if !routed.contains(pin.as_str()) && on_board(grid.cell(position), dimensions) {
+            obstacles.insert(snap(position));
+        }
+    }
+    for keepout in structure
+        .lists("keepout")
+        .chain(structure.lists("wire_keepout"))
+    {
+        for shape in keepout.shapes() {
+            let shape = shape.scaled(scale);
+            let (min, max) = shape.bounds();
+            let (first_row, first_column) = grid.cell((min.0, max.1));
+            let (last_row, last_column) = grid.cell((max.0, min.1));
+            for row in first_row.max(0)..=last_row.min(dimensions.0 as i64 - 1) {
+                for column in first_column.max(0)..=last_column.min(dimensions.1 as i64 - 1) {
+                    let cell = (row as u32, column as u32);
+                    if shape.contains(grid.centre(cell)) {
+                        obstacles.insert(cell);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(seed) = seed {
+        builder = builder.seed(seed);
+    }
+    let problem = builder
+        .obstacles(obstacles.into_iter().filter(|cell| !pins.contains(cell)))
+        .build()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    Ok((problem, design))
+}
+
+/// Writes the routes of `individual`, a solution of the problem imported with `design`, as an
+/// SES session. Every wire runs from the pad through the centres of the cells the route turns in
+/// to the other pad.
+pub fn write_ses(
+    design: &DsnDesign,
+    individual: &Individual,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let coordinate = |value: f64| (value * SES_RESOLUTION).round() as i64;
+    // Specctra strings can't escape their quote, so the routes pick one none of their names use.
+    let quote = IntoIterator::into_iter(['"', '\'', '$'])
+        .find(|&quote| {
+            !design.layer.contains(quote) && design.nets.iter().all(|net| !net.contains(quote))
+        })
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The net names use every Specctra quote character",
+            )
+        })?;
+    // The header comes before the parser settings, so it keeps the default quote.
+    let name = quoted(&design.name.replace('"', "'"), '"');
+
+    writeln!(writer, "(session {}", name)?;
+    writeln!(writer, "  (base_design {})", name)?;
+    writeln!(writer, "  (routes")?;
+    writeln!(writer, "    (resolution um 10)")?;
+    writeln!(writer, "    (parser (string_quote {}))", quote)?;
+    writeln!(writer, "    (network_out")?;
+    for ((name, &(start, end)), corners) in design
+        .nets
+        .iter()
+        .zip(&design.pads)
+        .zip(individual.polylines())
+    {
+        let mut path: Vec<(i64, i64)> = vec![];
+        let points = corners.into_iter().map(|cell| design.grid.centre(cell));
+        for (x, y) in std::iter::once(start)
+            .chain(points)
+            .chain(std::iter::once(end))
+        {
+            let point = (coordinate(x), coordinate(y));
+            if path.last() != Some(&point) {
+                path.push(point);
+            }
+        }
+
+        writeln!(writer, "      (net {}", quoted(name, quote))?;
+        if path.len() > 1 {
+            write!(
+                writer,
+                "        (wire (path {} {}",
+                quoted(&design.layer, quote),
+                coordinate(design.width)
+            )?;
+            for (x, y) in path {
+                write!(writer, " {} {}", x, y)?;
+            }
+            writeln!(writer, "))")?;
+        }
+        writeln!(writer, "      )")?;
+    }
+    writeln!(writer, "    )")?;
+    writeln!(writer, "  )")?;
+    writeln!(writer, ")")
+}
+
+/// Saves the routes of `individual` to the SES session at `path`, see `write_ses`.
+pub fn save_ses(design: &DsnDesign, individual: &Individual, path: &str) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_ses(design, individual, &mut writer)?;
+    writer.flush()
+}
+
+/// Positions in millimetres of all placed pins, keyed by `<component>-<pin>` as the network refers
+/// to them.
+fn pad_positions(pcb: &Expr, scale: f64) -> HashMap<String, (f64, f64)> {
+    let mut images = HashMap::new();
+    if let Some(library) = pcb.list("library") {
+        for image in library.lists("image") {
+            let pins: Vec<(&str, f64, f64)> = image
+                .lists("pin")
+                .filter_map(|pin| match pin.atoms().as_slice() {
+                    [_, id, x, y, ..] => Some((*id, x.parse().ok()?, y.parse().ok()?)),
+                    _ => None,
+                })
+                .collect();
+            if let Some(name) = image.atoms().first() {
+                images.insert(*name, pins);
+            }
+        }
+    }
+
+    let mut pads = HashMap::new();
+    let components = pcb
+        .list("placement")
+        .into_iter()
+        .flat_map(|placement| placement.lists("component"));
+    for component in components {
+        let pins = match component.atoms().first().and_then(|name| images.get(name)) {
+            Some(pins) => pins,
+            None => continue,
+        };
+        for place in component.lists("place") {
+            let (reference, x, y, side, rotation) = match place.atoms().as_slice() {
+                [reference, x, y, rest @ ..] => (
+                    *reference,
+                    x.parse().unwrap_or(0.),
+                    y.parse().unwrap_or(0.),
+                    rest.first().copied().unwrap_or("front"),
+                    rest.get(1)
+                        .and_then(|angle| angle.parse().ok())
+                        .unwrap_or(0.),
+                ),
+                _ => continue,
+            };
+            let (sin, cos) = f64::to_radians(rotation).sin_cos();
+            for &(id, dx, dy) in pins {
+                let dx = if side == "back" { -dx } else { dx };
+                let position = (
+                    (x + dx * cos - dy * sin) * scale,
+                    (y + dx * sin + dy * cos) * scale,
+                );
+                pads.insert(format!("{}-{}", reference, id), position);
+            }
+        }
+    }
+    pads
+}
+
+fn pad(pads: &HashMap<String, (f64, f64)>, pin: &str) -> io::Result<(f64, f64)> {
+    pads.get(pin)
+        .copied()
+        .ok_or_else(|| invalid(format!("Unknown pin {}", pin)))
+}
+
+fn on_board((row, column): (i64, i64), dimensions: (u32, u32)) -> bool {
+    (0..dimensions.0 as i64).contains(&row) && (0..dimensions.1 as i64).contains(&column)
+}
+
+fn unit_mm(unit: &str) -> Option<f64> {
+    match unit {
+        "inch" => Some(25.4),
+        "mil" => Some(0.0254),
+        "cm" => Some(10.),
+        "mm" => Some(1.),
+        "um" => Some(0.001),
+        _ => None,
+    }
+}
+
+/// `name` as a Specctra string, in `quote` characters if it is empty or contains whitespace,
+/// parentheses or one of the quote characters. `name` must not contain `quote` itself.
+fn quoted(name: &str, quote: char) -> String {
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || "()\"'$".contains(c)) {
+        format!("{}{}{}", quote, name, quote)
+    } else {
+        name.to_string()
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Boundary and keepout shapes.
+#[derive(Debug)]
+enum Shape {
+    Rect((f64, f64), (f64, f64)),
+    Circle((f64, f64), f64),
+    Polygon(Vec<(f64, f64)>),
+}
+
+impl Shape {
+    fn scaled(self, scale: f64) -> Shape {
+        let point = |(x, y): (f64, f64)| (x * scale, y * scale);
+        match self {
+            Shape::Rect(a, b) => Shape::Rect(point(a), point(b)),
+            Shape::Circle(centre, radius) => Shape::Circle(point(centre), radius * scale),
+            Shape::Polygon(points) => Shape::Polygon(points.into_iter().map(point).collect()),
+        }
+    }
+
+    fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points = match self {
+            Shape::Rect(a, b) => vec![*a, *b],
+            Shape::Circle((x, y), radius) => {
+                vec![(x - radius, y - radius), (x + radius, y + radius)]
+            }
+            Shape::Polygon(points) => points.clone(),
+        };
+        points.iter().fold(
+            (
+                (f64::INFINITY, f64::INFINITY),
+                (f64::NEG_INFINITY, f64::NEG_INFINITY),
+            ),
+            |(min, max), &(x, y)| ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y))),
+        )
+    }
+
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        match self {
+            Shape::Rect(..) => {
+                let (min, max) = self.bounds();
+                (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y)
+            }
+            Shape::Circle(centre, radius) => {
+                (x - centre.0).powi(2) + (y - centre.1).powi(2) <= radius.powi(2)
+            }
+            Shape::Polygon(points) => {
+                let mut inside = false;
+                for (i, &(x1, y1)) in points.iter().enumerate() {
+                    let (x2, y2) = points[(i + 1) % points.len()];
+                    if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// A parsed S-expression.
+#[derive(Debug)]
+enum Expr {
+    Atom(String),
+    List(Vec<Expr>),
+}
+
+impl Expr {
+    fn items(&self) -> &[Expr] {
+        match self {
+            Expr::List(items) => items,
+            Expr::Atom(_) => &[],
+        }
+    }
+
+    fn keyword(&self) -> Option<&str> {
+        match self.items().first() {
+            Some(Expr::Atom(keyword)) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// The atoms following the keyword.
+    fn atoms(&self) -> Vec<&str> {
+        self.items()
+            .iter()
+            .skip(1)
+            .filter_map(|item| match item {
+                Expr::Atom(atom) => Some(atom.as_str()),
+                Expr::List(_) => None,
+            })
+            .collect()
+    }
+
+    fn numbers(&self) -> Vec<f64> {
+        self.atoms()
+            .iter()
+            .filter_map(|atom| atom.parse().ok())
+            .collect()
+    }
+
+    /// The nested lists starting with `keyword`.
+    fn lists<'a>(&'a self, keyword: &'a str) -> impl Iterator<Item = &'a Expr> + 'a {
+        self.items()
+            .iter()
+            .filter(move |item| item.keyword() == Some(keyword))
+    }
+
+    fn list<'a>(&'a self, keyword: &'a str) -> Option<&'a Expr> {
+        self.lists(keyword).next()
+    }
+
+    /// The shapes nested in a boundary or keepout.
+    fn shapes(&self) -> Vec<Shape> {
+        self.items()
+            .iter()
+            .filter_map(|item| {
+                // The layer comes first, paths and polygons add an aperture width.
+                let numbers: Vec<f64> = item
+                    .atoms()
+                    .iter()
+                    .skip(1)
+                    .filter_map(|atom| atom.parse().ok())
+                    .collect();
+                let points = |numbers: &[f64]| {
+                    numbers
+                        .chunks_exact(2)
+                        .map(|pair| (pair[0], pair[1]))
+                        .collect()
+                };
+                match (item.keyword()?, numbers.as_slice()) {
+                    ("rect", [x1, y1, x2, y2, ..]) => Some(Shape::Rect((*x1, *y1), (*x2, *y2))),
+                    ("circle", [diameter, rest @ ..]) => {
+                        let centre = match rest {
+                            [x, y, ..] => (*x, *y),
+                            _ => (0., 0.),
+                        };
+                        Some(Shape::Circle(centre, diameter / 2.))
+                    }
+                    ("path" | "polygon", [_, rest @ ..]) if rest.len() >= 4 => {
+                        Some(Shape::Polygon(points(rest)))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses the S-expression in `text`, honouring the `string_quote` setting of DSN parsers.
+fn parse(text: &str) -> io::Result<Expr> {
+    let mut stack: Vec<Vec<Expr>> = vec![vec![]];
+    let mut quote = '"';
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => stack.push(vec![]),
+            ')' => {
+                let list = stack.pop().filter(|_| !stack.is_empty());
+                let list = list.ok_or_else(|| invalid("Unbalanced parentheses"))?;
+                stack.last_mut().unwrap().push(Expr::List(list));
+            }
+            c if c.is_whitespace() => {}
+            c if c == quote => {
+                let atom: String = chars.by_ref().take_while(|&c| c != quote).collect();
+                stack.last_mut().unwrap().push(Expr::Atom(atom));
+            }
+            c => {
+                let mut atom = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    atom.push(c);
+                    chars.next();
+                }
+                let sets_quote = atom == "string_quote";
+                stack.last_mut().unwrap().push(Expr::Atom(atom));
+                if sets_quote {
+                    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                        chars.next();
+                    }
+                    if let Some(c) = chars.next() {
+                        quote = c;
+                        stack.last_mut().unwrap().push(Expr::Atom(c.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    match (stack.pop(), stack.is_empty()) {
+        (Some(mut top), true) if top.len() == 1 => Ok(top.remove(0)),
+        _ => Err(invalid("Expected a single S-expression")),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,101 @@
+use super::*;
+use crate::problem::router::NetOrder;
+
+#[test]
+fn test_specctra_import_and_session() {
+    let dsn = r#"(pcb "test board"
+  (parser (string_quote ") (space_in_quoted_tokens on))
+  (resolution um 10)
+  (unit um)
+  (structure
+    (layer F.Cu (type signal))
+    (layer B.Cu (type signal))
+    (boundary (path pcb 0  0 0  10000 0  10000 -8000  0 -8000  0 0))
+    (keepout "" (rect F.Cu 4000 -1000 6000 -3000))
+    (rule (width 200) (clearance 200))
+  )
+  (placement
+    (component R (place R1 1500 -1500 front 0) (place R2 8500 -6500 front 180))
+  )
+  (library
+    (image R (pin Round (rotate 90) 1 0 0) (pin Round 2 0 -2000))
+    (padstack Round (shape (circle F.Cu 600)))
+  )
+  (network
+    (net "SIG A" (pins R1-1 R2-1))
+    (net GND (pins R1-2 R2-2 R3-1))
+  )
+)"#;
+    let (problem, design) = parse_dsn(dsn, 1., Some(0)).unwrap();
+    assert_eq!(problem.dimensions(), (8, 10));
+    assert_eq!(problem.pin_locations(), &[((1, 1), (6, 8))]);
+    assert_eq!(problem.net_names(), &[Some("SIG A".to_string())]);
+    assert_eq!(
+        problem.obstacles(),
+        &[(1, 4), (1, 5), (2, 4), (2, 5), (3, 1), (4, 8)]
+    );
+    assert_eq!(design.skipped, vec!["GND".to_string()]);
+    assert_eq!(design.layer, "F.Cu");
+    assert!((design.width - 0.2).abs() < 1e-9);
+
+    let individual = problem.astar_route(NetOrder::ShortestFirst);
+    assert_eq!(individual.fitness().collisions, 0);
+    let mut output = vec![];
+    write_ses(&design, &individual, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("(session \"test board\"\n"));
+    assert!(output.contains("(net \"SIG A\"\n"));
+    assert!(output.contains("(wire (path F.Cu 2000 15000 -15000 "));
+    assert!(output.contains(" 85000 -65000))"));
+    assert_eq!(output.matches('(').count(), output.matches(')').count());
+
+    assert!(parse_dsn(&dsn.replace("R2-1", "R9-1"), 1., None).is_err());
+    assert!(parse_dsn("(pcb board", 1., None).is_err());
+}
+
+#[test]
+fn test_specctra_shared_cells_and_quotes() {
+    let dsn = r#"(pcb board
+  (parser (string_quote '))
+  (unit mm)
+  (structure
+    (layer 'Top "copper"' (type signal))
+    (boundary (rect pcb 0 0 10 10))
+  )
+  (placement
+    (component P (place A 0.5 9.5 front 0) (place B 0.7 9.7 front 0) (place C 9.5 9.5 front 0))
+  )
+  (library
+    (image P (pin Round 1 0 0) (pin Round 2 0 -4) (pin Round 3 4 0))
+    (padstack Round (shape (circle Top 0.2)))
+  )
+  (network
+    (net 'say "hi"' (pins A-3 C-1))
+    (net near (pins A-1 C-2))
+    (net also_near (pins B-1 C-2))
+    (net short (pins A-2 B-2))
+  )
+)"#;
+    let (problem, design) = parse_dsn(dsn, 1., Some(0)).unwrap();
+    assert_eq!(problem.pin_locations(), &[((0, 4), (0, 9))]);
+    assert_eq!(problem.net_names(), &[Some("say \"hi\"".to_string())]);
+    assert_eq!(
+        design.skipped,
+        vec![
+            "near".to_string(),
+            "also_near".to_string(),
+            "short".to_string()
+        ]
+    );
+    assert_eq!(design.layer, "Top \"copper\"");
+
+    let individual = problem.lee_route();
+    let mut output = vec![];
+    write_ses(&design, &individual, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("(parser (string_quote '))"));
+    assert!(output.contains("(net 'say \"hi\"'\n"));
+    assert!(output.contains("(wire (path 'Top \"copper\"' "));
+    let session = parse(&output).unwrap();
+    assert_eq!(session.keyword(), Some("session"));
+}
@@ -0,0 +1,142 @@
+//! Steady-state replacement, which breeds a few offspring at a time into the running population
+//! instead of replacing it all at once.
+
+use super::adaptation::OperatorStats;
+use super::mutation::MutationKind;
+use super::{FnType, Individual, Problem};
+use rand::{rngs::StdRng, Rng};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// How offspring enter the population in `Problem::next_generation`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Replacement {
+    /// Every generation replaces the whole population with offspring.
+    Generational,
+    /// Every step breeds `offspring` children that replace members of the population in place,
+    /// chosen by `policy`. A generation consists of as many steps as it takes to breed a
+    /// population worth of children, so runs cost the same number of evaluations either way.
+    SteadyState {
+        /// Children bred in every step.
+        offspring: usize,
+        /// Which member of the population every child replaces.
+        policy: ReplacementPolicy,
+    },
+}
+
+/// Which member of the population a steady-state child replaces. A child never replaces an
+/// individual with a better fitness.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ReplacementPolicy {
+    /// The individual with the worst fitness.
+    Worst,
+    /// The worst of this many individuals drawn at random.
+    TournamentLoser(usize),
+}
+
+/// Member of the population ordered by fitness, so the worst one is on top of a `BinaryHeap`.
+#[derive(PartialEq)]
+struct Ranked {
+    fitness: f32,
+    index: usize,
+}
+
+impl Eq for Ranked {}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.fitness.total_cmp(&other.fitness)
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ReplacementPolicy {
+    /// Ranking of `population` that `loser` keeps up to date, only needed by `Worst`.
+    fn ranking(&self, population: &[(Individual, f32)]) -> BinaryHeap<Ranked> {
+        match *self {
+            ReplacementPolicy::Worst => population
+                .iter()
+                .enumerate()
+                .map(|(index, item)| Ranked {
+                    fitness: item.1,
+                    index,
+                })
+                .collect(),
+            ReplacementPolicy::TournamentLoser(_) => BinaryHeap::new(),
+        }
+    }
+
+    fn loser(
+        &self,
+        population: &[(Individual, f32)],
+        ranking: &BinaryHeap<Ranked>,
+        random: &mut StdRng,
+    ) -> usize {
+        match *self {
+            ReplacementPolicy::Worst => ranking.peek().unwrap().index,
+            ReplacementPolicy::TournamentLoser(size) => {
+                let worse =
+                    |a: &usize, b: &usize| population[*a].1.partial_cmp(&population[*b].1).unwrap();
+                rand::seq::index::sample(random, population.len(), size.clamp(1, population.len()))
+                    .into_iter()
+                    .max_by(worse)
+                    .unwrap()
+            }
+        }
+    }
+}
+
+impl Problem {
+    /// One generation of `Replacement::SteadyState`.
+    pub(super) fn steady_state(
+        &mut self,
+        selector: FnType,
+        offspring: usize,
+        policy: ReplacementPolicy,
+        operators: &[(MutationKind, f32)],
+        stats: &mut OperatorStats,
+        random: &mut StdRng,
+    ) {
+        let offspring = offspring.max(1);
+        let steps = (self.population.len() / offspring).max(1);
+        let mut ranking = policy.ranking(&self.population);
+        for _ in 0..steps {
+            let mut children = vec![];
+            for _ in 0..offspring {
+                let parent = selector(self, self.config.batch_size, random);
+                let other = selector(self, self.config.batch_size, random);
+                let crossover = random.gen::<f32>() < self.rates.crossover;
+                let child = self.offspring(&parent, &other, crossover, operators, stats, random);
+                self.evaluations += 1;
+                let points = child.evaluate();
+                children.push((child, points));
+            }
+
+            for (child, points) in children {
+                let loser = policy.loser(&self.population, &ranking, random);
+                if points <= self.population[loser].1 {
+                    self.population[loser] = (child, points);
+                    // Only `Worst` ranks the population, and its loser is the top of the heap.
+                    if let Some(mut worst) = ranking.peek_mut() {
+                        *worst = Ranked {
+                            fitness: points,
+                            index: loser,
+                        };
+                    }
+                    if let Some(selection) = self.selection_fitness.get_mut(loser) {
+                        *selection = points;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
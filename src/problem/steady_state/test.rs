@@ -0,0 +1,57 @@
+use super::*;
+use crate::problem::fixtures::crossing_problem;
+use crate::problem::{tournament_selection, GaConfig};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_steady_state_keeps_best() {
+    for policy in IntoIterator::into_iter([
+        ReplacementPolicy::Worst,
+        ReplacementPolicy::TournamentLoser(3),
+    ]) {
+        let mut problem = crossing_problem(
+            2,
+            GaConfig {
+                population: 20,
+                replacement: Replacement::SteadyState {
+                    offspring: 2,
+                    policy,
+                },
+                ..Default::default()
+            },
+        );
+        problem.init_population(20);
+        let mut random = StdRng::seed_from_u64(2);
+        let mut best = f32::INFINITY;
+        for _ in 0..10 {
+            problem.next_generation(tournament_selection, &mut random);
+            let generation_best = problem
+                .population
+                .iter()
+                .map(|item| item.1)
+                .fold(f32::INFINITY, f32::min);
+            assert!(generation_best <= best);
+            best = generation_best;
+            assert_eq!(problem.population.len(), 20);
+        }
+    }
+}
+
+#[test]
+fn test_worst_policy_picks_worst() {
+    let mut problem = crossing_problem(3, GaConfig::default());
+    problem.init_population(30);
+    let worst = (0..problem.population.len())
+        .max_by(|&a, &b| {
+            problem.population[a]
+                .1
+                .partial_cmp(&problem.population[b].1)
+                .unwrap()
+        })
+        .unwrap();
+
+    let policy = ReplacementPolicy::Worst;
+    let ranking = policy.ranking(&problem.population);
+    let loser = policy.loser(&problem.population, &ranking, &mut StdRng::seed_from_u64(3));
+    assert_eq!(problem.population[loser].1, problem.population[worst].1);
+}
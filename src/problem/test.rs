@@ -1,67 +1,79 @@
 use super::*;
+use crate::problem::fixtures::{assert_valid_individual, crossing_problem, CROSSING_PINS};
+use rand::Rng;
 
-fn setup() -> Individual {
-    let mut pin_locations = vec![];
-    pin_locations.push(((1, 3), (5, 3)));
-    generate_individual((6, 6), pin_locations, Some(1))
+#[test]
+fn test_random_search_returns_minimum() {
+    let mut problem = crossing_problem(5, GaConfig::default());
+    let result = problem.random_search(30, Some(1));
+    let points = result.fitness.total;
+    assert_eq!(points, result.individual.evaluate());
+    assert_eq!(result.evaluations, 30);
+
+    let mut random = StdRng::seed_from_u64(5);
+    let minimum = (0..30)
+        .map(|_| generate_individual((6, 6), CROSSING_PINS.to_vec(), Some(random.gen())).evaluate())
+        .fold(f32::INFINITY, f32::min);
+    assert_eq!(points, minimum);
 }
 
 #[test]
-fn test_mutation() {
-    let mut individual = setup();
+fn test_random_search_seeded_threads() {
+    let mut problem = crossing_problem(7, GaConfig::default());
+    let first = problem.random_search(31, Some(3));
+    let second = problem.random_search(31, Some(3));
+    assert_eq!(first.fitness, second.fitness);
+    assert_eq!(first.individual.distance(&second.individual), 0.);
+    assert_valid_individual(&first.individual, (6, 6), &CROSSING_PINS);
 
-    println!("{}", individual);
+    let mut minimum = f32::INFINITY;
+    for (thread, share) in IntoIterator::into_iter([11, 10, 10]).enumerate() {
+        let mut random = StdRng::seed_from_u64(7 + thread as u64);
+        for _ in 0..share {
+            let individual =
+                generate_individual((6, 6), CROSSING_PINS.to_vec(), Some(random.gen()));
+            minimum = minimum.min(individual.evaluate());
+        }
+    }
+    assert_eq!(first.fitness.total, minimum);
+}
 
-    individual.connections[0].mutate_segment((0.3, 0.7), (6, 6));
-    println!("{}", individual);
+#[test]
+#[should_panic(expected = "at least one iteration")]
+fn test_random_search_rejects_zero_iterations() {
+    crossing_problem(0, GaConfig::default()).random_search(0, Some(1));
+}
 
-    // for i in 0..100000 {
-    //     let mut pin_locations = vec![];
-    //     pin_locations.push(((1, 3), (5, 3)));
-    //     individual = generate_individual((6, 6), pin_locations, Some(i));
-    //     println!("{}", individual);
-    //     individual.connections[0].mutate_segment((0.3, 0.7), (6, 6));
-    //     println!("{}", individual);
-    //     println!("------------------------------");
-    //     individual.connections[0].following_points();
-    // }
+#[test]
+fn test_genetic_search_seed_determines_run() {
+    let config = GaConfig {
+        population: 10,
+        iterations: 3,
+        ..Default::default()
+    };
+    let first = crossing_problem(1, config.clone()).genetic_search(tournament_selection, Some(9));
+    let second = crossing_problem(2, config).genetic_search(tournament_selection, Some(9));
+    assert_eq!(first.seed, Some(9));
+    assert_eq!(first.history, second.history);
+    assert_eq!(first.individual.distance(&second.individual), 0.);
+}
 
-    individual.connections[0] = Connection {
-        start: (2, 2),
-        end: (5, 2),
-        segments: vec![
-            Segment {
-                length: 1,
-                direction: South,
-            },
-            Segment {
-                length: 1,
-                direction: East,
-            },
-            Segment {
-                length: 1,
-                direction: South,
-            },
-            Segment {
-                length: 1,
-                direction: West,
-            },
-            Segment {
-                length: 1,
-                direction: South,
-            },
-        ],
+#[test]
+#[should_panic(expected = "population of at least one")]
+fn test_genetic_search_rejects_empty_population() {
+    let config = GaConfig {
+        population: 0,
+        ..Default::default()
     };
-        println!("{}", individual);
-        individual.connections[0].mutate_segment((0.83, 0.05), (6, 6));
-        println!("{:?}", individual.connections[0]);
-        println!("{}", individual);
+    crossing_problem(0, config).genetic_search(tournament_selection, Some(0));
 }
 
 #[test]
-fn test_find_point() {
-    let individual = setup();
-    assert_eq!(individual.connections[0].find_point(0), (1, 0));
-    assert_eq!(individual.connections[0].find_point(1), (5, 0));
-    assert_eq!(individual.connections[0].find_point(2), (5, 3));
+#[should_panic(expected = "selection batch of at least one")]
+fn test_genetic_search_rejects_empty_batch() {
+    let config = GaConfig {
+        batch_size: 0,
+        ..Default::default()
+    };
+    crossing_problem(0, config).genetic_search(tournament_selection, Some(0));
 }